@@ -1,66 +1,236 @@
 use gpui::*;
+use gpui_component::Root;
 
 use crate::data::SaveData;
+use crate::data::difficulty::RunSettings;
+use crate::data::profile::{ProfileIndex, ProfileSummary};
+use crate::data::run_history::{RunRecord, load_history};
+use crate::data::wave_script::WaveScript;
 use crate::screens::play::{PlayScreen, PlayScreenEvent};
-use crate::screens::{lobby, shop, welcome};
+use crate::screens::wave_editor::{WaveEditorEvent, WaveEditorScreen};
+use crate::screens::{achievements, lobby, shop, statistics, welcome};
 
 #[derive(Clone, Copy, PartialEq)]
 pub enum Screen {
     Welcome,
     Lobby,
     Shop,
+    Achievements,
+    Statistics,
     Play,
+    WaveEditor,
 }
 
 pub struct SentinelsApp {
     current_screen: Screen,
+    pub profiles: ProfileIndex,
+    /// Progress of each profile for the welcome screen, read again when the
+    /// profile list changes or the welcome screen opens
+    profile_summaries: Vec<ProfileSummary>,
+    /// Save of the selected profile
     pub save_data: SaveData,
     play_screen: Option<Entity<PlayScreen>>,
+    /// Kept across test runs so unsaved edits survive them
+    wave_editor: Option<Entity<WaveEditorScreen>>,
+    /// Why the saved wave script is replaced by the built-in one, checked
+    /// again on each visit to the lobby
+    wave_script_error: Option<String>,
+    /// Read from disk when the statistics screen opens
+    run_history: Vec<RunRecord>,
 }
 
 impl SentinelsApp {
     pub fn new(_cx: &mut Context<Self>) -> Self {
+        let profiles = ProfileIndex::load();
+        let save_data = SaveData::load(&profiles.current().dir());
+        let profile_summaries = profiles.profiles.iter().map(ProfileSummary::read).collect();
         Self {
             current_screen: Screen::Welcome,
-            save_data: SaveData::load(),
+            profiles,
+            profile_summaries,
+            save_data,
             play_screen: None,
+            wave_editor: None,
+            wave_script_error: None,
+            run_history: Vec::new(),
         }
     }
 
     pub fn navigate_to(&mut self, screen: Screen, cx: &mut Context<Self>) {
         if screen == Screen::Play && self.play_screen.is_none() {
-            let save_data = self.save_data.clone();
-            let play = cx.new(|_cx| PlayScreen::new(&save_data));
-            cx.subscribe(&play, |this, _, event: &PlayScreenEvent, cx| match event {
-                PlayScreenEvent::ReturnToLobby => {
-                    this.navigate_to(Screen::Lobby, cx);
-                }
-            })
-            .detach();
-            self.play_screen = Some(play);
+            let settings = self.save_data.run_settings.clone();
+            let script = WaveScript::load().unwrap_or_else(|_| WaveScript::builtin());
+            self.create_play_screen(settings, script, cx);
+        }
+        if screen == Screen::Lobby {
+            self.wave_script_error = WaveScript::load().err();
+        }
+        if screen == Screen::WaveEditor && self.wave_editor.is_none() {
+            self.create_wave_editor(cx);
+        }
+        if screen == Screen::Welcome {
+            self.refresh_profile_summaries();
+        }
+        if screen == Screen::Statistics {
+            self.run_history = self
+                .save_data
+                .profile_dir()
+                .map(load_history)
+                .unwrap_or_default();
         }
         if screen != Screen::Play {
             self.play_screen = None;
         }
+        if screen != Screen::Play && screen != Screen::WaveEditor {
+            self.wave_editor = None;
+        }
         self.current_screen = screen;
         cx.notify();
     }
+
+    /// Switches to the profile `id` and opens its lobby
+    pub fn select_profile(&mut self, id: u32, cx: &mut Context<Self>) {
+        let Some(profile) = self.profiles.get(id) else {
+            return;
+        };
+        self.save_data = SaveData::load(&profile.dir());
+        self.profiles.set_last_used(id);
+        self.navigate_to(Screen::Lobby, cx);
+    }
+
+    /// Returns false when the name is refused
+    pub fn create_profile(&mut self, name: &str, cx: &mut Context<Self>) -> bool {
+        if self.profiles.create(name).is_none() {
+            return false;
+        }
+        self.refresh_profile_summaries();
+        cx.notify();
+        true
+    }
+
+    /// Returns false when the name is refused
+    pub fn rename_profile(&mut self, id: u32, name: &str, cx: &mut Context<Self>) -> bool {
+        if !self.profiles.rename(id, name) {
+            return false;
+        }
+        cx.notify();
+        true
+    }
+
+    pub fn duplicate_profile(&mut self, id: u32, cx: &mut Context<Self>) {
+        if self.profiles.duplicate(id).is_some() {
+            self.refresh_profile_summaries();
+        }
+        cx.notify();
+    }
+
+    pub fn delete_profile(&mut self, id: u32, cx: &mut Context<Self>) {
+        if self.profiles.delete(id) {
+            // The deleted profile may have been the loaded one
+            self.save_data = SaveData::load(&self.profiles.current().dir());
+            self.refresh_profile_summaries();
+        }
+        cx.notify();
+    }
+
+    fn refresh_profile_summaries(&mut self) {
+        self.profile_summaries = self
+            .profiles
+            .profiles
+            .iter()
+            .map(ProfileSummary::read)
+            .collect();
+    }
+
+    /// Starts a run with settings or waves other than the lobby's, e.g. a
+    /// daily challenge or a wave editor test
+    pub fn start_run(&mut self, settings: RunSettings, script: WaveScript, cx: &mut Context<Self>) {
+        self.create_play_screen(settings, script, cx);
+        self.navigate_to(Screen::Play, cx);
+    }
+
+    fn create_play_screen(
+        &mut self,
+        settings: RunSettings,
+        script: WaveScript,
+        cx: &mut Context<Self>,
+    ) {
+        let save_data = self.save_data.clone();
+        let back_to = if settings.test_wave.is_some() {
+            Screen::WaveEditor
+        } else {
+            Screen::Lobby
+        };
+        let play = cx.new(|_cx| PlayScreen::new(&save_data, settings, script));
+        cx.subscribe(
+            &play,
+            move |this, _, event: &PlayScreenEvent, cx| match event {
+                PlayScreenEvent::ReturnToLobby => {
+                    // The run wrote its rewards to disk, pick them up
+                    this.save_data = SaveData::load(&this.profiles.current().dir());
+                    this.navigate_to(back_to, cx);
+                }
+            },
+        )
+        .detach();
+        self.play_screen = Some(play);
+    }
+
+    fn create_wave_editor(&mut self, cx: &mut Context<Self>) {
+        let script = WaveScript::load().unwrap_or_else(|_| WaveScript::builtin());
+        let editor = cx.new(|_cx| WaveEditorScreen::new(script));
+        cx.subscribe(
+            &editor,
+            |this, _, event: &WaveEditorEvent, cx| match event {
+                WaveEditorEvent::Back => {
+                    this.navigate_to(Screen::Lobby, cx);
+                }
+                WaveEditorEvent::TestRun { script, wave } => {
+                    let settings = RunSettings {
+                        test_wave: Some(*wave),
+                        ..RunSettings::default()
+                    };
+                    this.start_run(settings, script.clone(), cx);
+                }
+            },
+        )
+        .detach();
+        self.wave_editor = Some(editor);
+    }
 }
 
 impl Render for SentinelsApp {
-    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         div()
             .size_full()
             .bg(rgb(0x1a1a2e))
             .child(match self.current_screen {
-                Screen::Welcome => welcome::render(cx).into_any_element(),
-                Screen::Lobby => lobby::render(&self.save_data, cx).into_any_element(),
+                Screen::Welcome => {
+                    welcome::render(&self.profiles, &self.profile_summaries, cx).into_any_element()
+                }
+                Screen::Lobby => lobby::render(
+                    &self.save_data,
+                    &self.profiles.current().name,
+                    self.wave_script_error.as_deref(),
+                    cx,
+                )
+                .into_any_element(),
                 Screen::Shop => shop::render(&mut self.save_data, cx).into_any_element(),
+                Screen::Statistics => statistics::render(&self.run_history, cx).into_any_element(),
+                Screen::Achievements => {
+                    achievements::render(&self.save_data, cx).into_any_element()
+                }
                 Screen::Play => self
                     .play_screen
                     .clone()
                     .expect("PlayScreen should be created by navigate_to()")
                     .into_any_element(),
+                Screen::WaveEditor => self
+                    .wave_editor
+                    .clone()
+                    .expect("WaveEditorScreen should be created by navigate_to()")
+                    .into_any_element(),
             })
+            .children(Root::render_dialog_layer(window, cx))
     }
 }
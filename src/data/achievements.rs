@@ -0,0 +1,98 @@
+use serde::{Deserialize, Serialize};
+
+/// Steam reactions to trigger in a single run for `SteamMaster`
+pub const STEAM_REACTIONS_GOAL: u32 = 100;
+/// Wave to reach with the shield locked for `NoShieldWave20`
+pub const NO_SHIELD_WAVE: u32 = 20;
+/// Wave to clear with nothing but Sentinelles for `OldGuard`
+pub const OLD_GUARD_WAVE: u32 = 15;
+pub const SURVIVOR_WAVE: u32 = 30;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Achievement {
+    FirstBoss,
+    CoupDeGrace,
+    SteamMaster,
+    NoShieldWave20,
+    OldGuard,
+    Wave30,
+}
+
+/// Granted once, when the achievement unlocks
+#[derive(Clone, Copy)]
+pub enum AchievementReward {
+    Pepites(u32),
+    Skin(TowerSkin),
+}
+
+impl Achievement {
+    pub fn all() -> &'static [Achievement] {
+        &[
+            Achievement::FirstBoss,
+            Achievement::CoupDeGrace,
+            Achievement::SteamMaster,
+            Achievement::NoShieldWave20,
+            Achievement::OldGuard,
+            Achievement::Wave30,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Achievement::FirstBoss => "Tueur de geants",
+            Achievement::CoupDeGrace => "Coup de grace",
+            Achievement::SteamMaster => "Maitre de la vapeur",
+            Achievement::NoShieldWave20 => "Sans filet",
+            Achievement::OldGuard => "Vieille garde",
+            Achievement::Wave30 => "Increvable",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Achievement::FirstBoss => "Vaincre un boss",
+            Achievement::CoupDeGrace => "Achever un boss avec l'attaque du joueur",
+            Achievement::SteamMaster => "Declencher 100 reactions Vapeur en une partie",
+            Achievement::NoShieldWave20 => "Atteindre la vague 20 sans bouclier",
+            Achievement::OldGuard => "Terminer la vague 15 en ne construisant que des Sentinelles",
+            Achievement::Wave30 => "Atteindre la vague 30",
+        }
+    }
+
+    pub fn reward(&self) -> AchievementReward {
+        match self {
+            Achievement::FirstBoss => AchievementReward::Pepites(25),
+            Achievement::CoupDeGrace => AchievementReward::Pepites(75),
+            Achievement::SteamMaster => AchievementReward::Skin(TowerSkin::Vapor),
+            Achievement::NoShieldWave20 => AchievementReward::Pepites(200),
+            Achievement::OldGuard => AchievementReward::Skin(TowerSkin::Gilded),
+            Achievement::Wave30 => AchievementReward::Skin(TowerSkin::Obsidian),
+        }
+    }
+
+    /// e.g. "+75 pepites" or "Apparence Doree"
+    pub fn reward_label(&self) -> String {
+        match self.reward() {
+            AchievementReward::Pepites(pepites) => format!("+{} pepites", pepites),
+            AchievementReward::Skin(skin) => format!("Apparence {}", skin.name()),
+        }
+    }
+}
+
+/// Cosmetic look applied to every tower, unlocked by achievements
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum TowerSkin {
+    Gilded,
+    Obsidian,
+    Vapor,
+}
+
+impl TowerSkin {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TowerSkin::Gilded => "Doree",
+            TowerSkin::Obsidian => "Obsidienne",
+            TowerSkin::Vapor => "Vapeur",
+        }
+    }
+}
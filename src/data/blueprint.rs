@@ -0,0 +1,209 @@
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::PathBuf;
+
+use crate::data::tower_defs::TowerKind;
+use crate::game::Point2D;
+use crate::game::tower::Tower;
+
+/// Prefix of shareable blueprint codes, bumped if the format changes
+const CODE_PREFIX: &str = "SB2:";
+/// Codes from before the "Perforation" upgrades, migrated on import
+const LEGACY_CODE_PREFIX: &str = "SB1:";
+/// Upgrade added after `LEGACY_CODE_PREFIX` codes were made
+const LEGACY_MISSING_UPGRADE: &str = "Perforation";
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct BlueprintTower {
+    pub kind: TowerKind,
+    pub x: f32,
+    pub y: f32,
+    /// Target level of each upgrade, in `Tower::get_upgrades` order
+    pub upgrade_levels: Vec<u32>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Blueprint {
+    pub name: String,
+    pub towers: Vec<BlueprintTower>,
+}
+
+impl Blueprint {
+    pub fn from_towers(name: String, towers: &[Tower]) -> Self {
+        Self {
+            name,
+            towers: towers
+                .iter()
+                .map(|t| BlueprintTower {
+                    kind: t.kind,
+                    x: t.position.x,
+                    y: t.position.y,
+                    upgrade_levels: t.upgrade_levels(),
+                })
+                .collect(),
+        }
+    }
+
+    pub fn load_all() -> Vec<Self> {
+        let mut blueprints: Vec<Self> = std::fs::read_dir(Self::blueprints_dir())
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok())
+                    .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+                    .filter_map(|entry| std::fs::read_to_string(entry.path()).ok())
+                    .filter_map(|s| serde_json::from_str(&s).ok())
+                    .collect()
+            })
+            .unwrap_or_default();
+        blueprints.sort_by(|a, b| a.name.cmp(&b.name));
+        blueprints
+    }
+
+    /// "Plan N" with the lowest N whose file doesn't exist yet
+    pub fn unused_name() -> String {
+        let dir = Self::blueprints_dir();
+        let mut n = 1;
+        loop {
+            let name = format!("Plan {}", n);
+            if !dir.join(file_name(&name)).exists() {
+                return name;
+            }
+            n += 1;
+        }
+    }
+
+    /// Writes the blueprint to a new file. Returns false without writing
+    /// anything if a file with its name already exists.
+    pub fn save(&self) -> bool {
+        let dir = Self::blueprints_dir();
+        std::fs::create_dir_all(&dir).ok();
+        let Ok(json) = serde_json::to_string_pretty(self) else {
+            return false;
+        };
+        std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(dir.join(file_name(&self.name)))
+            .and_then(|mut file| file.write_all(json.as_bytes()))
+            .is_ok()
+    }
+
+    /// Compact text form: `SB2:kind,x,y,l1.l2.l3;kind,x,y,...`
+    pub fn to_code(&self) -> String {
+        let towers: Vec<String> = self
+            .towers
+            .iter()
+            .map(|t| {
+                let kind_idx = TowerKind::all()
+                    .iter()
+                    .position(|k| *k == t.kind)
+                    .unwrap_or(0);
+                let levels: Vec<String> = t.upgrade_levels.iter().map(|l| l.to_string()).collect();
+                format!(
+                    "{},{},{},{}",
+                    kind_idx,
+                    t.x.round() as i32,
+                    t.y.round() as i32,
+                    levels.join(".")
+                )
+            })
+            .collect();
+        format!("{}{}", CODE_PREFIX, towers.join(";"))
+    }
+
+    pub fn from_code(name: String, code: &str) -> Option<Self> {
+        let code = code.trim();
+        let (body, legacy) = match code.strip_prefix(CODE_PREFIX) {
+            Some(body) => (body, false),
+            None => (code.strip_prefix(LEGACY_CODE_PREFIX)?, true),
+        };
+        let mut towers = Vec::new();
+        for entry in body.split(';').filter(|e| !e.is_empty()) {
+            let mut fields = entry.split(',');
+            let kind = *TowerKind::all().get(fields.next()?.parse::<usize>().ok()?)?;
+            let x = fields.next()?.parse::<f32>().ok()?;
+            let y = fields.next()?.parse::<f32>().ok()?;
+            let upgrade_levels = match fields.next() {
+                Some(levels) if !levels.is_empty() => levels
+                    .split('.')
+                    .map(|l| l.parse::<u32>().ok())
+                    .collect::<Option<Vec<u32>>>()?,
+                _ => Vec::new(),
+            };
+            let upgrade_levels = if legacy {
+                migrate_legacy_levels(kind, upgrade_levels)
+            } else {
+                upgrade_levels
+            };
+            towers.push(BlueprintTower {
+                kind,
+                x,
+                y,
+                upgrade_levels,
+            });
+        }
+        Some(Self { name, towers })
+    }
+
+    fn blueprints_dir() -> PathBuf {
+        dirs::home_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join(".sentinels")
+            .join("blueprints")
+    }
+}
+
+/// File of the blueprint called `name`: its slug, lowercase
+fn file_name(name: &str) -> String {
+    let slug: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    format!("{}.json", slug)
+}
+
+/// Inserts a zero level for each "Perforation" upgrade, which legacy codes
+/// don't have, so the following levels land on the right upgrades again
+fn migrate_legacy_levels(kind: TowerKind, levels: Vec<u32>) -> Vec<u32> {
+    let tower = Tower::from_def(0, kind, Point2D::zero());
+    let mut legacy = levels.into_iter();
+    tower
+        .get_upgrades()
+        .iter()
+        .map(|(_, name, _)| {
+            if *name == LEGACY_MISSING_UPGRADE {
+                0
+            } else {
+                legacy.next().unwrap_or(0)
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn legacy_codes_get_their_perforation_level() {
+        let kind_idx = TowerKind::all()
+            .iter()
+            .position(|k| *k == TowerKind::Seisme)
+            .unwrap();
+        let code = format!("SB1:{},10,20,1.2.3.4", kind_idx);
+        let blueprint = Blueprint::from_code("Test".to_string(), &code).unwrap();
+        // Portee, Vitesse, Degats, Zone, then the new Perforation
+        assert_eq!(blueprint.towers[0].upgrade_levels, vec![1, 2, 3, 4, 0]);
+
+        let current = blueprint.to_code();
+        assert!(current.starts_with("SB2:"));
+        let reloaded = Blueprint::from_code("Test".to_string(), &current).unwrap();
+        assert_eq!(reloaded.towers[0].upgrade_levels, vec![1, 2, 3, 4, 0]);
+    }
+}
@@ -0,0 +1,129 @@
+use crate::game::elemental::TowerElement;
+
+#[derive(Clone, Copy)]
+pub enum BossPhaseKind {
+    /// Fires `count` projectiles at the core every `interval` seconds
+    Volley { count: u32, interval: f32 },
+    /// Spawns `count` enemies of preset `enemy` around the boss every `interval` seconds
+    Summon {
+        enemy: &'static str,
+        count: u32,
+        interval: f32,
+    },
+    /// Multiplies speed once and ignores damage of `immune` for `duration` seconds
+    Enrage {
+        speed_multiplier: f32,
+        immune: TowerElement,
+        duration: f32,
+    },
+}
+
+pub struct BossPhase {
+    pub name: &'static str,
+    /// Phase starts once HP drops to this share of max HP
+    pub hp_threshold: f32,
+    pub kind: BossPhaseKind,
+}
+
+pub struct BossDef {
+    pub name: &'static str,
+    pub hp_multiplier: f32,
+    /// Ordered by decreasing threshold
+    pub phases: &'static [BossPhase],
+}
+
+pub const BOSSES: &[BossDef] = &[
+    BossDef {
+        name: "Colosse",
+        hp_multiplier: 1.0,
+        phases: &[
+            BossPhase {
+                name: "Salve",
+                hp_threshold: 0.7,
+                kind: BossPhaseKind::Volley {
+                    count: 5,
+                    interval: 3.0,
+                },
+            },
+            BossPhase {
+                name: "Renforts",
+                hp_threshold: 0.4,
+                kind: BossPhaseKind::Summon {
+                    enemy: "soldat",
+                    count: 3,
+                    interval: 5.0,
+                },
+            },
+            BossPhase {
+                name: "Rage",
+                hp_threshold: 0.15,
+                kind: BossPhaseKind::Enrage {
+                    speed_multiplier: 1.8,
+                    immune: TowerElement::Fire,
+                    duration: 5.0,
+                },
+            },
+        ],
+    },
+    BossDef {
+        name: "Leviathan",
+        hp_multiplier: 1.3,
+        phases: &[
+            BossPhase {
+                name: "Nuee",
+                hp_threshold: 0.75,
+                kind: BossPhaseKind::Summon {
+                    enemy: "eclaireur",
+                    count: 4,
+                    interval: 4.0,
+                },
+            },
+            BossPhase {
+                name: "Deluge",
+                hp_threshold: 0.5,
+                kind: BossPhaseKind::Volley {
+                    count: 8,
+                    interval: 2.5,
+                },
+            },
+            BossPhase {
+                name: "Maree",
+                hp_threshold: 0.2,
+                kind: BossPhaseKind::Enrage {
+                    speed_multiplier: 2.0,
+                    immune: TowerElement::Water,
+                    duration: 6.0,
+                },
+            },
+        ],
+    },
+    BossDef {
+        name: "Titan",
+        hp_multiplier: 1.6,
+        phases: &[
+            BossPhase {
+                name: "Carapace",
+                hp_threshold: 0.6,
+                kind: BossPhaseKind::Enrage {
+                    speed_multiplier: 1.5,
+                    immune: TowerElement::Earth,
+                    duration: 4.0,
+                },
+            },
+            BossPhase {
+                name: "Barrage",
+                hp_threshold: 0.3,
+                kind: BossPhaseKind::Volley {
+                    count: 12,
+                    interval: 2.0,
+                },
+            },
+        ],
+    },
+];
+
+/// Boss fought on a boss wave, cycling through `BOSSES` every 5 waves
+pub fn boss_for_wave(wave_number: u32) -> &'static BossDef {
+    let idx = (wave_number / 5).saturating_sub(1) as usize % BOSSES.len();
+    &BOSSES[idx]
+}
@@ -0,0 +1,101 @@
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::difficulty::{Difficulty, Mutator, RunSettings};
+use super::tower_defs::TowerKind;
+
+/// Towers offered by a daily challenge, the Sentinelle included
+const DAILY_TOWER_COUNT: usize = 5;
+
+/// Result of the first attempt at a daily challenge; replays don't change it
+#[derive(Clone, Serialize, Deserialize)]
+pub struct DailyResult {
+    /// Days since 1970-01-01 (UTC)
+    pub day: u32,
+    pub score: u32,
+    pub wave: u32,
+    /// False while the ranked attempt is still running, or was abandoned
+    pub finished: bool,
+}
+
+/// Run everyone gets on a given day: same seed, settings and towers
+pub struct DailyChallenge {
+    pub day: u32,
+    pub seed: u64,
+    pub settings: RunSettings,
+    pub towers: Vec<TowerKind>,
+}
+
+impl DailyChallenge {
+    pub fn today() -> Self {
+        Self::for_day(current_day())
+    }
+
+    pub fn for_day(day: u32) -> Self {
+        let seed = (day as u64 + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        let difficulty =
+            [Difficulty::Normal, Difficulty::Hard, Difficulty::Nightmare][rng.gen_range(0..3)];
+        let mutators = Mutator::all()
+            .iter()
+            .copied()
+            .filter(|_| rng.gen_bool(0.4))
+            .collect();
+
+        let mut others: Vec<TowerKind> = TowerKind::all()
+            .iter()
+            .copied()
+            .filter(|k| *k != TowerKind::Sentinelle)
+            .collect();
+        others.shuffle(&mut rng);
+        others.truncate(DAILY_TOWER_COUNT - 1);
+        // Keep the build menu order
+        let towers = TowerKind::all()
+            .iter()
+            .copied()
+            .filter(|k| *k == TowerKind::Sentinelle || others.contains(k))
+            .collect();
+
+        Self {
+            day,
+            seed,
+            settings: RunSettings {
+                difficulty,
+                mutators,
+                daily: Some(day),
+                test_wave: None,
+            },
+            towers,
+        }
+    }
+
+    /// "YYYY-MM-DD"
+    pub fn date_label(&self) -> String {
+        let (year, month, day) = civil_from_days(self.day as i64);
+        format!("{:04}-{:02}-{:02}", year, month, day)
+    }
+}
+
+fn current_day() -> u32 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| (d.as_secs() / 86_400) as u32)
+}
+
+/// Gregorian date of a day count since the Unix epoch (H. Hinnant's algorithm)
+pub fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
@@ -0,0 +1,165 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+    Nightmare,
+}
+
+impl Difficulty {
+    pub fn all() -> &'static [Difficulty] {
+        &[
+            Difficulty::Easy,
+            Difficulty::Normal,
+            Difficulty::Hard,
+            Difficulty::Nightmare,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Facile",
+            Difficulty::Normal => "Normal",
+            Difficulty::Hard => "Difficile",
+            Difficulty::Nightmare => "Cauchemar",
+        }
+    }
+
+    pub fn enemy_hp_multiplier(&self) -> f32 {
+        match self {
+            Difficulty::Easy => 0.75,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 1.3,
+            Difficulty::Nightmare => 1.7,
+        }
+    }
+
+    pub fn enemy_speed_multiplier(&self) -> f32 {
+        match self {
+            Difficulty::Easy => 0.9,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 1.1,
+            Difficulty::Nightmare => 1.2,
+        }
+    }
+
+    /// Scales the enemy count of regular spawn groups; bosses are left alone
+    pub fn wave_size_multiplier(&self) -> f32 {
+        match self {
+            Difficulty::Easy => 0.8,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 1.2,
+            Difficulty::Nightmare => 1.5,
+        }
+    }
+
+    /// Gold at the start of a run, before the shop bonus
+    pub fn starting_gold(&self) -> u32 {
+        match self {
+            Difficulty::Easy => 700,
+            Difficulty::Normal => 500,
+            Difficulty::Hard => 400,
+            Difficulty::Nightmare => 300,
+        }
+    }
+
+    /// Preparation time between two waves, in seconds
+    pub fn between_waves_duration(&self) -> f32 {
+        match self {
+            Difficulty::Easy => 8.0,
+            Difficulty::Normal => 5.0,
+            Difficulty::Hard => 4.0,
+            Difficulty::Nightmare => 3.0,
+        }
+    }
+
+    /// Applied to the pepites banked at the end of the run
+    pub fn pepite_multiplier(&self) -> f32 {
+        match self {
+            Difficulty::Easy => 0.5,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 1.5,
+            Difficulty::Nightmare => 2.0,
+        }
+    }
+}
+
+/// Optional rule changes stacked on top of the difficulty
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Mutator {
+    NoShield,
+    DoubleBosses,
+    EnemyRegen,
+}
+
+impl Mutator {
+    pub fn all() -> &'static [Mutator] {
+        &[
+            Mutator::NoShield,
+            Mutator::DoubleBosses,
+            Mutator::EnemyRegen,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Mutator::NoShield => "Sans bouclier",
+            Mutator::DoubleBosses => "Boss doubles",
+            Mutator::EnemyRegen => "Ennemis regenerants",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Mutator::NoShield => "Le bouclier d'energie est desactive",
+            Mutator::DoubleBosses => "Chaque boss arrive accompagne d'un second",
+            Mutator::EnemyRegen => "Tous les ennemis regenerent 1% de leurs PV par seconde",
+        }
+    }
+}
+
+/// Difficulty and mutators of a run, picked in the lobby
+#[derive(Clone, Default, PartialEq, Debug, Serialize, Deserialize)]
+pub struct RunSettings {
+    pub difficulty: Difficulty,
+    pub mutators: Vec<Mutator>,
+    /// Day of the daily challenge this run plays, if any
+    #[serde(default)]
+    pub daily: Option<u32>,
+    /// Wave editor test run starting at this wave; nothing is saved
+    #[serde(default)]
+    pub test_wave: Option<u32>,
+}
+
+impl RunSettings {
+    pub fn has(&self, mutator: Mutator) -> bool {
+        self.mutators.contains(&mutator)
+    }
+
+    pub fn toggle(&mut self, mutator: Mutator) {
+        if let Some(pos) = self.mutators.iter().position(|m| *m == mutator) {
+            self.mutators.remove(pos);
+        } else {
+            self.mutators.push(mutator);
+        }
+    }
+
+    /// e.g. "Difficile + Sans bouclier, Boss doubles"
+    pub fn label(&self) -> String {
+        let mut label = self.difficulty.name().to_string();
+        if !self.mutators.is_empty() {
+            let mutators: Vec<&str> = self.mutators.iter().map(|m| m.name()).collect();
+            label = format!("{} + {}", label, mutators.join(", "));
+        }
+        if self.daily.is_some() {
+            label = format!("Defi du jour - {}", label);
+        }
+        if let Some(wave) = self.test_wave {
+            label = format!("Test vague {} - {}", wave, label);
+        }
+        label
+    }
+}
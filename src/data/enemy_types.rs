@@ -1,8 +1,88 @@
-use crate::game::enemy::EnemyShape;
+use gpui::Hsla;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::OnceLock;
 
+use crate::game::ability::EnemyAbility;
+use crate::game::enemy::ArmorKind;
+
+/// Built-in archetypes, shipped with the game
+const BUILTIN_ENEMIES: &str = include_str!("enemies.json");
+
+/// Scaling stops growing past this factor, so long runs can't overflow
+const MAX_SCALING_FACTOR: f32 = 1.0e6;
+
+/// How a stat grows with the wave number
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum ScalingCurve {
+    /// `1 + per_wave * wave`
+    Linear { per_wave: f32 },
+    /// `(1 + rate) ^ wave`
+    Exponential { rate: f32 },
+}
+
+impl ScalingCurve {
+    pub fn factor(&self, wave_number: u32) -> f32 {
+        let factor = match self {
+            ScalingCurve::Linear { per_wave } => 1.0 + per_wave * wave_number as f32,
+            ScalingCurve::Exponential { rate } => (1.0 + rate).powi(wave_number as i32),
+        };
+        factor.min(MAX_SCALING_FACTOR)
+    }
+
+    /// Stats never shrink as waves go on
+    fn validate(&self) -> Result<(), String> {
+        let rate = match self {
+            ScalingCurve::Linear { per_wave } => *per_wave,
+            ScalingCurve::Exponential { rate } => *rate,
+        };
+        if rate >= 0.0 && rate.is_finite() {
+            Ok(())
+        } else {
+            Err(format!("croissance invalide ({})", rate))
+        }
+    }
+}
+
+fn default_hp_scaling() -> ScalingCurve {
+    ScalingCurve::Linear { per_wave: 0.04 }
+}
+
+fn default_damage_scaling() -> ScalingCurve {
+    ScalingCurve::Linear { per_wave: 0.02 }
+}
+
+fn default_armor_kind() -> ArmorKind {
+    ArmorKind::Flat
+}
+
+/// Body drawn for an enemy: a regular polygon in an HSL color
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct EnemyVisual {
+    pub sides: u32,
+    pub hue: f32,
+    pub saturation: f32,
+    pub lightness: f32,
+}
+
+impl EnemyVisual {
+    pub fn color(&self) -> Hsla {
+        Hsla {
+            h: self.hue,
+            s: self.saturation,
+            l: self.lightness,
+            a: 1.0,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct EnemyPreset {
-    pub shape: EnemyShape,
-    pub name: &'static str,
+    /// Key used by waves, abilities and bosses to reference this archetype
+    pub id: String,
+    pub name: String,
+    pub visual: EnemyVisual,
     pub base_hp: f32,
     pub base_speed: f32,
     pub base_damage: f32,
@@ -10,69 +90,188 @@ pub struct EnemyPreset {
     pub attack_speed: f32,
     pub gold_value: u32,
     pub radius: f32,
+    #[serde(default = "default_hp_scaling")]
+    pub hp_scaling: ScalingCurve,
+    #[serde(default = "default_damage_scaling")]
+    pub damage_scaling: ScalingCurve,
+    /// Shoots the nearest tower in range instead of the core
+    #[serde(default)]
+    pub targets_towers: bool,
+    /// Runs the boss phase script of its wave and drops boss rewards
+    #[serde(default)]
+    pub is_boss: bool,
+    #[serde(default)]
+    pub abilities: Vec<EnemyAbility>,
+    #[serde(default = "default_armor_kind")]
+    pub armor_kind: ArmorKind,
+    /// Flat points removed per hit, or share of damage blocked for `Percent`
+    #[serde(default)]
+    pub armor: f32,
+    #[serde(default)]
+    pub armor_per_wave: f32,
+}
+
+impl EnemyPreset {
+    /// Checks the stats a preset file could get wrong. References to other
+    /// presets are checked once every file is loaded.
+    fn validate(&self) -> Result<(), String> {
+        if self.visual.sides < 3 {
+            return Err(format!(
+                "{} cotes, il en faut au moins 3",
+                self.visual.sides
+            ));
+        }
+        let positive = [
+            ("base_hp", self.base_hp),
+            ("attack_speed", self.attack_speed),
+            ("radius", self.radius),
+        ];
+        let non_negative = [
+            ("base_speed", self.base_speed),
+            ("base_damage", self.base_damage),
+            ("attack_range", self.attack_range),
+            ("armor", self.armor),
+            ("armor_per_wave", self.armor_per_wave),
+        ];
+        for (field, value) in positive {
+            if !(value > 0.0 && value.is_finite()) {
+                return Err(format!("{} doit etre positif ({})", field, value));
+            }
+        }
+        for (field, value) in non_negative {
+            if !(value >= 0.0 && value.is_finite()) {
+                return Err(format!("{} ne peut pas etre negatif ({})", field, value));
+            }
+        }
+        self.hp_scaling.validate()?;
+        self.damage_scaling.validate()?;
+        for ability in &self.abilities {
+            ability.validate()?;
+        }
+        Ok(())
+    }
 }
 
-pub const ENEMY_PRESETS: &[EnemyPreset] = &[
-    EnemyPreset {
-        shape: EnemyShape::Triangle,
-        name: "Eclaireur",
-        base_hp: 18.0,
-        base_speed: 130.0,
-        base_damage: 3.0,
-        attack_range: 45.0,
-        attack_speed: 0.8,
-        gold_value: 5,
-        radius: 10.0,
-    },
-    EnemyPreset {
-        shape: EnemyShape::Square,
-        name: "Soldat",
-        base_hp: 40.0,
-        base_speed: 85.0,
-        base_damage: 6.0,
-        attack_range: 55.0,
-        attack_speed: 0.6,
-        gold_value: 10,
-        radius: 13.0,
-    },
-    EnemyPreset {
-        shape: EnemyShape::Pentagon,
-        name: "Tank",
-        base_hp: 80.0,
-        base_speed: 45.0,
-        base_damage: 10.0,
-        attack_range: 65.0,
-        attack_speed: 0.4,
-        gold_value: 20,
-        radius: 17.0,
-    },
-    EnemyPreset {
-        shape: EnemyShape::Hexagon,
-        name: "Destructeur",
-        base_hp: 35.0,
-        base_speed: 70.0,
-        base_damage: 15.0,
-        attack_range: 75.0,
-        attack_speed: 0.9,
-        gold_value: 15,
-        radius: 14.0,
-    },
-    EnemyPreset {
-        shape: EnemyShape::Octagon,
-        name: "Boss",
-        base_hp: 200.0,
-        base_speed: 30.0,
-        base_damage: 25.0,
-        attack_range: 90.0,
-        attack_speed: 0.5,
-        gold_value: 60,
-        radius: 24.0,
-    },
-];
-
-pub fn get_preset(shape: EnemyShape) -> &'static EnemyPreset {
-    ENEMY_PRESETS
-        .iter()
-        .find(|p| p.shape == shape)
-        .unwrap_or(&ENEMY_PRESETS[0])
+/// Presets in use, and why some user files or presets were left out
+struct LoadedPresets {
+    presets: Vec<EnemyPreset>,
+    errors: Vec<String>,
+}
+
+fn loaded() -> &'static LoadedPresets {
+    static PRESETS: OnceLock<LoadedPresets> = OnceLock::new();
+    PRESETS.get_or_init(load_presets)
+}
+
+/// Every known archetype: the built-in ones, then those found in
+/// ~/.sentinels/enemies/*.json (each file holds a list of presets). A user
+/// preset with the id of an existing one replaces it.
+pub fn all_presets() -> &'static [EnemyPreset] {
+    &loaded().presets
+}
+
+/// Problems found loading the user presets, one line per file or preset
+pub fn load_errors() -> &'static [String] {
+    &loaded().errors
+}
+
+pub fn get_preset(id: &str) -> Option<&'static EnemyPreset> {
+    all_presets().iter().find(|p| p.id == id)
+}
+
+fn load_presets() -> LoadedPresets {
+    let builtin: Vec<EnemyPreset> =
+        serde_json::from_str(BUILTIN_ENEMIES).expect("built-in enemies.json is valid");
+    // Each preset with the file it came from, for error messages
+    let mut presets: Vec<(EnemyPreset, String)> = builtin
+        .into_iter()
+        .map(|p| (p, "enemies.json".to_string()))
+        .collect();
+    let mut errors = Vec::new();
+
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(presets_dir())
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+                .collect()
+        })
+        .unwrap_or_default();
+    paths.sort();
+
+    for path in paths {
+        let file = path
+            .file_name()
+            .map_or_else(String::new, |f| f.to_string_lossy().into_owned());
+        let parsed = std::fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|s| serde_json::from_str::<Vec<EnemyPreset>>(&s).map_err(|e| e.to_string()));
+        let user_presets = match parsed {
+            Ok(user_presets) => user_presets,
+            Err(e) => {
+                errors.push(format!("{}: {}", file, e));
+                continue;
+            }
+        };
+        for preset in user_presets {
+            if let Err(e) = preset.validate() {
+                errors.push(format!("{}: ennemi '{}' ignore, {}", file, preset.id, e));
+                continue;
+            }
+            match presets.iter_mut().find(|(p, _)| p.id == preset.id) {
+                Some(existing) => *existing = (preset, file.clone()),
+                None => presets.push((preset, file.clone())),
+            }
+        }
+    }
+
+    // Abilities spawning an unknown preset would silently do nothing
+    let ids: Vec<String> = presets.iter().map(|(p, _)| p.id.clone()).collect();
+    for (preset, file) in &mut presets {
+        preset.abilities.retain(|ability| {
+            let Some(spawned) = ability.spawned_enemy() else {
+                return true;
+            };
+            let known = ids.iter().any(|id| id == spawned);
+            if !known {
+                errors.push(format!(
+                    "{}: ennemi '{}', capacite ignoree, ennemi '{}' inconnu",
+                    file, preset.id, spawned
+                ));
+            }
+            known
+        });
+    }
+
+    LoadedPresets {
+        presets: presets.into_iter().map(|(p, _)| p).collect(),
+        errors,
+    }
+}
+
+fn presets_dir() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".sentinels")
+        .join("enemies")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_presets_pass_validation() {
+        let presets: Vec<EnemyPreset> = serde_json::from_str(BUILTIN_ENEMIES).unwrap();
+        for preset in &presets {
+            assert!(preset.validate().is_ok(), "{}", preset.id);
+        }
+    }
+
+    #[test]
+    fn exponential_scaling_stays_finite() {
+        let curve = ScalingCurve::Exponential { rate: 0.5 };
+        assert_eq!(curve.factor(10_000), MAX_SCALING_FACTOR);
+    }
 }
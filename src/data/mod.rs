@@ -1,8 +1,22 @@
+pub mod achievements;
+pub mod blueprint;
+pub mod boss_defs;
+pub mod daily;
+pub mod difficulty;
 pub mod enemy_types;
+pub mod profile;
+pub mod run_history;
 pub mod tower_defs;
+pub mod wave_script;
 
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+use achievements::{Achievement, AchievementReward, TowerSkin};
+use daily::DailyResult;
+use difficulty::RunSettings;
+
+const SAVE_FILE: &str = "save.json";
 
 #[derive(Serialize, Deserialize, Clone, Default)]
 pub struct SaveData {
@@ -10,6 +24,23 @@ pub struct SaveData {
     pub best_score: u32,
     pub max_wave: u32,
     pub shop_upgrades: Vec<ShopUpgradeState>,
+    /// Settings of the run that set `best_score`
+    #[serde(default)]
+    pub best_score_settings: Option<RunSettings>,
+    /// Last settings picked in the lobby, used for the next run
+    #[serde(default)]
+    pub run_settings: RunSettings,
+    #[serde(default)]
+    pub daily_results: Vec<DailyResult>,
+    #[serde(default)]
+    pub achievements: Vec<Achievement>,
+    /// Skin picked on the achievements screen, None for the default look
+    #[serde(default)]
+    pub tower_skin: Option<TowerSkin>,
+    /// Folder of the profile this save belongs to, None for a save that is
+    /// never written (e.g. the default one used by tests)
+    #[serde(skip)]
+    dir: Option<PathBuf>,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -18,11 +49,30 @@ pub struct ShopUpgradeState {
     pub level: u32,
 }
 
+/// What one level of a shop upgrade grants in every run
+#[derive(Clone, Copy)]
+pub enum ShopEffect {
+    StartingGold(u32),
+    PlayerDamagePercent(f32),
+    PlayerRange(f32),
+    PlayerHp(f32),
+    GoldEarnPercent(f32),
+    /// The shield is unlocked by the first level and grows with the others
+    ShieldLevel,
+    TowerSlots(u32),
+    /// Raises the interest rate (in percent) and cap on banked gold
+    Interest {
+        percent: f32,
+        cap: u32,
+    },
+}
+
 pub struct ShopUpgradeDef {
     pub id: &'static str,
     pub name: &'static str,
     pub description: &'static str,
     pub max_level: u32,
+    pub effect: ShopEffect,
     base_cost: u32,
     cost_per_level: u32,
 }
@@ -39,6 +89,7 @@ pub const SHOP_UPGRADES: &[ShopUpgradeDef] = &[
         name: "Or de depart",
         description: "+50 or au debut de chaque partie",
         max_level: 10,
+        effect: ShopEffect::StartingGold(50),
         base_cost: 100,
         cost_per_level: 50,
     },
@@ -47,6 +98,7 @@ pub const SHOP_UPGRADES: &[ShopUpgradeDef] = &[
         name: "Degats du joueur",
         description: "+5% degats de base du joueur",
         max_level: 10,
+        effect: ShopEffect::PlayerDamagePercent(5.0),
         base_cost: 150,
         cost_per_level: 75,
     },
@@ -55,6 +107,7 @@ pub const SHOP_UPGRADES: &[ShopUpgradeDef] = &[
         name: "Portee du joueur",
         description: "+10 portee d'attaque du joueur",
         max_level: 5,
+        effect: ShopEffect::PlayerRange(10.0),
         base_cost: 200,
         cost_per_level: 100,
     },
@@ -63,6 +116,7 @@ pub const SHOP_UPGRADES: &[ShopUpgradeDef] = &[
         name: "Points de vie",
         description: "+20 PV max du joueur",
         max_level: 10,
+        effect: ShopEffect::PlayerHp(20.0),
         base_cost: 100,
         cost_per_level: 50,
     },
@@ -71,6 +125,7 @@ pub const SHOP_UPGRADES: &[ShopUpgradeDef] = &[
         name: "Or gagne",
         description: "+10% or gagne par ennemi",
         max_level: 5,
+        effect: ShopEffect::GoldEarnPercent(10.0),
         base_cost: 250,
         cost_per_level: 125,
     },
@@ -79,6 +134,7 @@ pub const SHOP_UPGRADES: &[ShopUpgradeDef] = &[
         name: "Bouclier d'energie",
         description: "Bloque les ennemis a distance, +50 PV par niveau",
         max_level: 5,
+        effect: ShopEffect::ShieldLevel,
         base_cost: 100,
         cost_per_level: 200,
     },
@@ -87,30 +143,51 @@ pub const SHOP_UPGRADES: &[ShopUpgradeDef] = &[
         name: "Slots de tourelles",
         description: "+1 emplacement de tourelle par niveau",
         max_level: 10,
+        effect: ShopEffect::TowerSlots(1),
         base_cost: 150,
         cost_per_level: 100,
     },
+    ShopUpgradeDef {
+        id: "interest",
+        name: "Interets",
+        description: "+1% d'interets entre les vagues, plafond +25 or",
+        max_level: 5,
+        effect: ShopEffect::Interest {
+            percent: 1.0,
+            cap: 25,
+        },
+        base_cost: 200,
+        cost_per_level: 150,
+    },
 ];
 
 impl SaveData {
-    pub fn load() -> Self {
-        let path = Self::save_path();
-        std::fs::read_to_string(&path)
+    /// Save of the profile stored in `dir`, a fresh one if there is none
+    pub fn load(dir: &Path) -> Self {
+        let mut data: Self = std::fs::read_to_string(dir.join(SAVE_FILE))
             .ok()
             .and_then(|s| serde_json::from_str(&s).ok())
-            .unwrap_or_default()
+            .unwrap_or_default();
+        data.dir = Some(dir.to_path_buf());
+        data
     }
 
+    /// Writes the save into its profile folder, if it has one
     pub fn save(&self) {
-        let path = Self::save_path();
-        if let Some(parent) = path.parent() {
-            std::fs::create_dir_all(parent).ok();
-        }
+        let Some(dir) = &self.dir else {
+            return;
+        };
+        std::fs::create_dir_all(dir).ok();
         if let Ok(json) = serde_json::to_string_pretty(self) {
-            std::fs::write(path, json).ok();
+            std::fs::write(dir.join(SAVE_FILE), json).ok();
         }
     }
 
+    /// Folder of the profile, also holding its run history
+    pub fn profile_dir(&self) -> Option<&Path> {
+        self.dir.as_deref()
+    }
+
     pub fn get_upgrade_level(&self, id: &str) -> u32 {
         self.shop_upgrades
             .iter()
@@ -135,10 +212,52 @@ impl SaveData {
         self.save();
     }
 
-    fn save_path() -> PathBuf {
-        dirs::home_dir()
-            .unwrap_or_else(|| PathBuf::from("."))
-            .join(".sentinels")
-            .join("save.json")
+    pub fn daily_result(&self, day: u32) -> Option<&DailyResult> {
+        self.daily_results.iter().find(|r| r.day == day)
+    }
+
+    /// Registers an attempt at a daily challenge. Only the first one is
+    /// ranked: returns true if this is it.
+    pub fn begin_daily(&mut self, day: u32) -> bool {
+        if self.daily_result(day).is_some() {
+            return false;
+        }
+        self.daily_results.push(DailyResult {
+            day,
+            score: 0,
+            wave: 0,
+            finished: false,
+        });
+        self.save();
+        true
+    }
+
+    pub fn has_achievement(&self, achievement: Achievement) -> bool {
+        self.achievements.contains(&achievement)
+    }
+
+    /// Records an achievement and grants its reward. Returns false if it was
+    /// already unlocked.
+    pub fn unlock_achievement(&mut self, achievement: Achievement) -> bool {
+        if self.has_achievement(achievement) {
+            return false;
+        }
+        self.achievements.push(achievement);
+        if let AchievementReward::Pepites(pepites) = achievement.reward() {
+            self.pepites += pepites;
+        }
+        self.save();
+        true
+    }
+
+    /// Skins granted by the unlocked achievements
+    pub fn unlocked_skins(&self) -> Vec<TowerSkin> {
+        self.achievements
+            .iter()
+            .filter_map(|a| match a.reward() {
+                AchievementReward::Skin(skin) => Some(skin),
+                AchievementReward::Pepites(_) => None,
+            })
+            .collect()
     }
 }
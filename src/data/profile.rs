@@ -0,0 +1,280 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use super::SaveData;
+
+/// Longest profile name kept on rename, in characters
+const MAX_NAME_LEN: usize = 24;
+/// Files moved into the first profile when upgrading from a single save
+const PROFILE_FILES: &[&str] = &["save.json", "history.jsonl"];
+
+/// A player sharing the machine. Each one gets its own folder holding its
+/// save (settings included) and run history.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Profile {
+    /// Folder name under ~/.sentinels/profiles/, never reused
+    pub id: u32,
+    pub name: String,
+}
+
+impl Profile {
+    pub fn dir(&self) -> PathBuf {
+        profiles_dir().join(self.id.to_string())
+    }
+}
+
+/// Progress shown next to a profile on the welcome screen
+pub struct ProfileSummary {
+    pub id: u32,
+    pub best_score: u32,
+    pub max_wave: u32,
+    pub pepites: u32,
+}
+
+impl ProfileSummary {
+    /// Reads the profile's save from disk
+    pub fn read(profile: &Profile) -> Self {
+        let save = SaveData::load(&profile.dir());
+        Self {
+            id: profile.id,
+            best_score: save.best_score,
+            max_wave: save.max_wave,
+            pepites: save.pepites,
+        }
+    }
+}
+
+/// List of profiles, stored in ~/.sentinels/profiles.json
+#[derive(Serialize, Deserialize)]
+pub struct ProfileIndex {
+    pub profiles: Vec<Profile>,
+    next_id: u32,
+    /// Profile picked last, selected on the next launch
+    pub last_used: Option<u32>,
+}
+
+impl ProfileIndex {
+    /// Always holds at least one profile. A missing or unreadable index is
+    /// rebuilt from the profile folders on disk.
+    pub fn load() -> Self {
+        let index: Option<Self> = std::fs::read_to_string(index_path())
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok());
+        match index {
+            Some(index) if !index.profiles.is_empty() => index,
+            _ => Self::rebuild(),
+        }
+    }
+
+    /// Lists the folders under ~/.sentinels/profiles/. Their names were lost
+    /// with the index, so they become "Profil <id>". With no folder at all,
+    /// the old single save is moved into a first profile named "Joueur".
+    fn rebuild() -> Self {
+        let mut ids: Vec<u32> = std::fs::read_dir(profiles_dir())
+            .map(|entries| {
+                entries
+                    .filter_map(|e| e.ok())
+                    .filter(|e| e.path().is_dir())
+                    .filter_map(|e| e.file_name().to_str()?.parse().ok())
+                    .collect()
+            })
+            .unwrap_or_default();
+        ids.sort_unstable();
+        let mut index = Self {
+            profiles: ids
+                .iter()
+                .map(|&id| Profile {
+                    id,
+                    name: format!("Profil {id}"),
+                })
+                .collect(),
+            next_id: ids.last().map_or(1, |id| id + 1),
+            last_used: None,
+        };
+        if index.profiles.is_empty() {
+            let id = index.add("Joueur".to_string());
+            let dir = index.get(id).map(Profile::dir).unwrap_or_default();
+            for file in PROFILE_FILES {
+                std::fs::rename(sentinels_dir().join(file), dir.join(file)).ok();
+            }
+        } else {
+            index.save();
+        }
+        index
+    }
+
+    pub fn save(&self) {
+        let path = index_path();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).ok();
+        }
+        if let Ok(json) = serde_json::to_string_pretty(self) {
+            std::fs::write(path, json).ok();
+        }
+    }
+
+    pub fn get(&self, id: u32) -> Option<&Profile> {
+        self.profiles.iter().find(|p| p.id == id)
+    }
+
+    /// Last used profile, or the first one
+    pub fn current(&self) -> &Profile {
+        self.last_used
+            .and_then(|id| self.get(id))
+            .unwrap_or(&self.profiles[0])
+    }
+
+    pub fn set_last_used(&mut self, id: u32) {
+        self.last_used = Some(id);
+        self.save();
+    }
+
+    /// Adds an empty profile named `name` and returns its id, None if the
+    /// name is refused
+    pub fn create(&mut self, name: &str) -> Option<u32> {
+        let name = self.clean_name(name, None)?;
+        Some(self.add(name))
+    }
+
+    /// Renames a profile; blank names and names of other profiles are
+    /// refused, long ones cut
+    pub fn rename(&mut self, id: u32, name: &str) -> bool {
+        let Some(name) = self.clean_name(name, Some(id)) else {
+            return false;
+        };
+        let Some(profile) = self.profiles.iter_mut().find(|p| p.id == id) else {
+            return false;
+        };
+        profile.name = name;
+        self.save();
+        true
+    }
+
+    /// `base`, or `base 2`, `base 3`... when another profile already has it.
+    /// `base` is cut so the number always fits.
+    pub fn unused_name(&self, base: &str) -> String {
+        (1..)
+            .find_map(|n: usize| {
+                let suffix = if n == 1 {
+                    String::new()
+                } else {
+                    format!(" {n}")
+                };
+                let base: String = base.chars().take(MAX_NAME_LEN - suffix.len()).collect();
+                self.clean_name(&format!("{}{suffix}", base.trim_end()), None)
+            })
+            .unwrap_or_default()
+    }
+
+    /// `name` trimmed and cut to MAX_NAME_LEN, None if it is blank or taken
+    /// by a profile other than `id`
+    fn clean_name(&self, name: &str, id: Option<u32>) -> Option<String> {
+        let name: String = name.trim().chars().take(MAX_NAME_LEN).collect();
+        let taken = self
+            .profiles
+            .iter()
+            .any(|p| Some(p.id) != id && p.name == name);
+        (!name.is_empty() && !name.contains('\n') && !taken).then_some(name)
+    }
+
+    fn add(&mut self, name: String) -> u32 {
+        let profile = Profile {
+            id: self.next_id,
+            name,
+        };
+        self.next_id += 1;
+        std::fs::create_dir_all(profile.dir()).ok();
+        let id = profile.id;
+        self.profiles.push(profile);
+        self.save();
+        id
+    }
+
+    /// Copies a profile's save and history into a new profile
+    pub fn duplicate(&mut self, id: u32) -> Option<u32> {
+        let source = self.get(id)?.clone();
+        let copy = self.add(self.unused_name(&format!("{} (copie)", source.name)));
+        let dir = self.get(copy)?.dir();
+        copy_files(&source.dir(), &dir);
+        Some(copy)
+    }
+
+    /// Deletes a profile and its files. The last profile can't be deleted.
+    pub fn delete(&mut self, id: u32) -> bool {
+        if self.profiles.len() <= 1 {
+            return false;
+        }
+        let Some(pos) = self.profiles.iter().position(|p| p.id == id) else {
+            return false;
+        };
+        let profile = self.profiles.remove(pos);
+        std::fs::remove_dir_all(profile.dir()).ok();
+        if self.last_used == Some(id) {
+            self.last_used = None;
+        }
+        self.save();
+        true
+    }
+}
+
+fn copy_files(from: &Path, to: &Path) {
+    let Ok(entries) = std::fs::read_dir(from) else {
+        return;
+    };
+    for entry in entries.filter_map(|e| e.ok()) {
+        if entry.path().is_file() {
+            std::fs::copy(entry.path(), to.join(entry.file_name())).ok();
+        }
+    }
+}
+
+fn index_path() -> PathBuf {
+    sentinels_dir().join("profiles.json")
+}
+
+fn profiles_dir() -> PathBuf {
+    sentinels_dir().join("profiles")
+}
+
+fn sentinels_dir() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".sentinels")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index(names: &[&str]) -> ProfileIndex {
+        ProfileIndex {
+            profiles: (1..)
+                .zip(names)
+                .map(|(id, name)| Profile {
+                    id,
+                    name: name.to_string(),
+                })
+                .collect(),
+            next_id: names.len() as u32 + 1,
+            last_used: None,
+        }
+    }
+
+    #[test]
+    fn unused_name_numbers_taken_names_within_the_length_limit() {
+        let long = "x".repeat(MAX_NAME_LEN + 4);
+        let index = index(&["Profil", "Profil 2", &long[..MAX_NAME_LEN]]);
+        assert_eq!(index.unused_name("Profil"), "Profil 3");
+        let numbered = index.unused_name(&long);
+        assert!(numbered.ends_with(" 2"));
+        assert_eq!(numbered.chars().count(), MAX_NAME_LEN);
+    }
+
+    #[test]
+    fn names_of_other_profiles_are_refused() {
+        let index = index(&["Alice", "Bob"]);
+        assert_eq!(index.clean_name("  Bob ", None), None);
+        assert_eq!(index.clean_name("Bob", Some(2)).as_deref(), Some("Bob"));
+        assert_eq!(index.clean_name("   ", None), None);
+    }
+}
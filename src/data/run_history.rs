@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::daily::civil_from_days;
+use super::difficulty::RunSettings;
+use super::tower_defs::TowerKind;
+
+/// One finished run, appended to the history file at game over
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RunRecord {
+    /// Seconds since the Unix epoch when the run ended
+    pub ended_at: u64,
+    /// Seed of the wave rolls, replays the same waves
+    pub seed: u64,
+    pub settings: RunSettings,
+    pub score: u32,
+    pub wave: u32,
+    /// Towers placed per kind, undone placements left out
+    pub towers_built: Vec<(TowerKind, u32)>,
+    pub gold_earned: u32,
+    /// Pepites banked at the end of the run, after the difficulty multiplier
+    pub pepites: u32,
+    pub cause_of_death: String,
+    /// Game time in seconds
+    pub duration: f32,
+}
+
+impl RunRecord {
+    pub fn now() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs())
+    }
+
+    /// "YYYY-MM-DD"
+    pub fn date_label(&self) -> String {
+        let (year, month, day) = civil_from_days((self.ended_at / 86_400) as i64);
+        format!("{:04}-{:02}-{:02}", year, month, day)
+    }
+
+    /// "12:34"
+    pub fn duration_label(&self) -> String {
+        let seconds = self.duration as u32;
+        format!("{}:{:02}", seconds / 60, seconds % 60)
+    }
+
+    pub fn built(&self, kind: TowerKind) -> u32 {
+        self.towers_built
+            .iter()
+            .find(|(k, _)| *k == kind)
+            .map_or(0, |(_, count)| *count)
+    }
+
+    /// Adds the run as one line of the history file in the profile `dir`
+    pub fn append(&self, dir: &Path) {
+        let path = history_path(dir);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).ok();
+        }
+        let Ok(json) = serde_json::to_string(self) else {
+            return;
+        };
+        if let Ok(mut file) = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+        {
+            writeln!(file, "{}", json).ok();
+        }
+    }
+}
+
+/// Every run recorded in the profile `dir`, oldest first. Lines that don't
+/// parse are skipped.
+pub fn load_history(dir: &Path) -> Vec<RunRecord> {
+    std::fs::read_to_string(history_path(dir))
+        .map(|s| {
+            s.lines()
+                .filter_map(|line| serde_json::from_str(line).ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn history_path(dir: &Path) -> PathBuf {
+    dir.join("history.jsonl")
+}
@@ -1,8 +1,9 @@
+use serde::{Deserialize, Serialize};
 use std::f64::INFINITY;
 
 use crate::game::elemental::TowerElement;
 
-#[derive(Clone, Copy, PartialEq, Debug)]
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
 pub enum TowerKind {
     Sentinelle,
     Inferno,
@@ -53,6 +54,8 @@ pub enum TowerAction {
     ApplyDamage {
         target: EffectTarget,
         damage: DamageType,
+        /// Share of the target's armor ignored (0.0 - 1.0)
+        armor_pierce: f32,
     },
     ApplyEffect {
         target: EffectTarget,
@@ -77,6 +80,7 @@ pub enum ActionUpgradeTarget {
     EffectRatio,
     MaxTargets,
     GoldPerSecond,
+    ArmorPierce,
 }
 
 #[derive(Clone)]
@@ -129,6 +133,7 @@ pub struct TowerDef {
     pub description: &'static str,
     pub element: TowerElement,
     pub base_cost: u32,
+    pub max_hp: f32,
     pub projectile_size: f32,
     pub range: UpgradeableProp,
     pub attack_speed: UpgradeableProp,
@@ -155,6 +160,7 @@ pub enum ResolvedAction {
     ApplyDamage {
         target: EffectTarget,
         damage: ResolvedDamage,
+        armor_pierce: f32,
     },
     ApplyEffect {
         target: EffectTarget,
@@ -168,7 +174,11 @@ pub enum ResolvedAction {
 impl TowerActionDef {
     pub fn resolve(&self) -> ResolvedAction {
         match &self.action {
-            TowerAction::ApplyDamage { target, damage } => {
+            TowerAction::ApplyDamage {
+                target,
+                damage,
+                armor_pierce,
+            } => {
                 let mut resolved_target = target.clone();
                 let resolved_damage = match damage {
                     DamageType::Fixed(base) => {
@@ -190,7 +200,8 @@ impl TowerActionDef {
                         ResolvedDamage::PercentHp(val)
                     }
                 };
-                // Apply AoeRadius and MaxTargets upgrades
+                let mut resolved_pierce = *armor_pierce;
+                // Apply AoeRadius, MaxTargets and ArmorPierce upgrades
                 for u in &self.upgrades {
                     match u.applies_to {
                         ActionUpgradeTarget::AoeRadius => {
@@ -203,12 +214,16 @@ impl TowerActionDef {
                                 *n += (u.prop.bonus_per_level * u.prop.current_level as f32) as u32;
                             }
                         }
+                        ActionUpgradeTarget::ArmorPierce => {
+                            resolved_pierce += u.prop.bonus_per_level * u.prop.current_level as f32;
+                        }
                         _ => {}
                     }
                 }
                 ResolvedAction::ApplyDamage {
                     target: resolved_target,
                     damage: resolved_damage,
+                    armor_pierce: resolved_pierce.min(1.0),
                 }
             }
             TowerAction::ApplyEffect { target, effect } => {
@@ -306,6 +321,7 @@ pub struct TowerBuilder {
     description: &'static str,
     element: TowerElement,
     base_cost: u32,
+    max_hp: f32,
     projectile_size: f32,
     range: (f32, f32, u32),
     attack_speed: (f32, f32, u32),
@@ -320,6 +336,7 @@ impl TowerBuilder {
             description: "",
             element,
             base_cost: 50,
+            max_hp: 100.0,
             projectile_size: 4.0,
             range: (100.0, 15.0, 5),
             attack_speed: (1.0, 0.15, 5),
@@ -337,6 +354,11 @@ impl TowerBuilder {
         self
     }
 
+    pub fn hp(mut self, hp: f32) -> Self {
+        self.max_hp = hp;
+        self
+    }
+
     pub fn projectile_size(mut self, size: f32) -> Self {
         self.projectile_size = size;
         self
@@ -395,6 +417,10 @@ impl TowerBuilder {
                             0.0
                         }
                     }
+                    (
+                        TowerAction::ApplyDamage { armor_pierce, .. },
+                        ActionUpgradeTarget::ArmorPierce,
+                    ) => *armor_pierce,
                     (TowerAction::ApplyEffect { effect, .. }, ActionUpgradeTarget::EffectDps) => {
                         if let EffectType::Burn { dps, .. } = effect {
                             *dps
@@ -451,6 +477,7 @@ impl TowerBuilder {
             description: self.description,
             element: self.element,
             base_cost: self.base_cost,
+            max_hp: self.max_hp,
             projectile_size: self.projectile_size,
             range: UpgradeableProp {
                 base: self.range.0,
@@ -486,6 +513,7 @@ pub fn all_tower_defs() -> Vec<TowerDef> {
                 TowerAction::ApplyDamage {
                     target: EffectTarget::Single,
                     damage: DamageType::Fixed(8.0),
+                    armor_pierce: 0.0,
                 },
                 vec![("Degats", ActionUpgradeTarget::Damage, 3.0, 5)],
             )
@@ -499,6 +527,7 @@ pub fn all_tower_defs() -> Vec<TowerDef> {
                 TowerAction::ApplyDamage {
                     target: EffectTarget::Area(45.0),
                     damage: DamageType::Fixed(10.0),
+                    armor_pierce: 0.0,
                 },
                 vec![
                     ("Degats", ActionUpgradeTarget::Damage, 3.0, 5),
@@ -528,6 +557,7 @@ pub fn all_tower_defs() -> Vec<TowerDef> {
                 TowerAction::ApplyDamage {
                     target: EffectTarget::Single,
                     damage: DamageType::Fixed(5.0),
+                    armor_pierce: 0.0,
                 },
                 vec![("Degats", ActionUpgradeTarget::Damage, 3.0, 5)],
             )
@@ -554,6 +584,7 @@ pub fn all_tower_defs() -> Vec<TowerDef> {
                 TowerAction::ApplyDamage {
                     target: EffectTarget::Multi(3),
                     damage: DamageType::Fixed(7.0),
+                    armor_pierce: 0.0,
                 },
                 vec![
                     ("Degats", ActionUpgradeTarget::Damage, 3.0, 5),
@@ -564,16 +595,19 @@ pub fn all_tower_defs() -> Vec<TowerDef> {
         TowerBuilder::new(TowerKind::Seisme, "Tour Seisme", TowerElement::Earth)
             .description("Degats massifs de zone")
             .cost(110)
+            .hp(160.0)
             .range(90.0, 15.0, 5)
             .attack_speed(0.35, 0.05, 5)
             .action_with_upgrades(
                 TowerAction::ApplyDamage {
                     target: EffectTarget::Area(55.0),
                     damage: DamageType::Fixed(18.0),
+                    armor_pierce: 0.2,
                 },
                 vec![
                     ("Degats", ActionUpgradeTarget::Damage, 4.0, 5),
                     ("Zone", ActionUpgradeTarget::AoeRadius, 10.0, 5),
+                    ("Perforation", ActionUpgradeTarget::ArmorPierce, 0.1, 4),
                 ],
             )
             .build(),
@@ -590,13 +624,18 @@ pub fn all_tower_defs() -> Vec<TowerDef> {
                         range: 200.0,
                     },
                     damage: DamageType::Fixed(18.0),
+                    armor_pierce: 0.5,
                 },
-                vec![("Degats", ActionUpgradeTarget::Damage, 4.0, 5)],
+                vec![
+                    ("Degats", ActionUpgradeTarget::Damage, 4.0, 5),
+                    ("Perforation", ActionUpgradeTarget::ArmorPierce, 0.1, 5),
+                ],
             )
             .build(),
         TowerBuilder::new(TowerKind::Forge, "Forge", TowerElement::Earth)
             .description("Genere de l'or passivement")
             .cost(150)
+            .hp(80.0)
             .range(0.0, 0.0, 0)
             .attack_speed(0.0, 0.0, 0)
             .action_with_upgrades(
@@ -609,6 +648,7 @@ pub fn all_tower_defs() -> Vec<TowerDef> {
         TowerBuilder::new(TowerKind::Alarme, "Alarme", TowerElement::Electric)
             .description("Notifications systeme configurables")
             .cost(75)
+            .hp(60.0)
             .build(),
     ]
 }
@@ -0,0 +1,352 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::data::enemy_types::get_preset;
+
+/// Built-in script, shipped with the game
+const BUILTIN_WAVES: &str = include_str!("waves.json");
+
+/// Enemy spawned by a group: a fixed preset id, or a weighted mix rolled
+/// for each enemy, e.g. `[["eclaireur", 0.85], ["soldat", 0.15]]`
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum EnemyPick {
+    Id(String),
+    Mix(Vec<(String, f32)>),
+}
+
+impl EnemyPick {
+    pub fn roll(&self, rng: &mut impl Rng) -> &str {
+        match self {
+            EnemyPick::Id(id) => id,
+            EnemyPick::Mix(weights) => {
+                let total: f32 = weights.iter().map(|(_, w)| w).sum();
+                let mut r = rng.r#gen::<f32>() * total;
+                for (id, weight) in weights {
+                    if r < *weight {
+                        return id;
+                    }
+                    r -= weight;
+                }
+                weights.last().map_or("", |(id, _)| id)
+            }
+        }
+    }
+}
+
+/// Rejects picks that could roll an unknown preset, which would spawn nothing
+fn validate_pick(pick: &EnemyPick) -> Result<(), String> {
+    let ids: Vec<&str> = match pick {
+        EnemyPick::Id(id) => vec![id],
+        EnemyPick::Mix(weights) => {
+            if weights.iter().any(|(_, w)| !(*w >= 0.0 && w.is_finite()))
+                || weights.iter().map(|(_, w)| w).sum::<f32>() <= 0.0
+            {
+                return Err("poids de melange invalides".to_string());
+            }
+            weights.iter().map(|(id, _)| id.as_str()).collect()
+        }
+    };
+    match ids.into_iter().find(|id| get_preset(id).is_none()) {
+        Some(unknown) => Err(format!("ennemi '{}' inconnu", unknown)),
+        None => Ok(()),
+    }
+}
+
+/// Where a group enters the map
+#[derive(Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum SpawnSide {
+    /// Any edge, rolled per enemy (or per group for formations)
+    #[default]
+    Random,
+    Top,
+    Bottom,
+    Left,
+    Right,
+    /// Fixed world position, the core being at the origin
+    Point {
+        x: f32,
+        y: f32,
+    },
+}
+
+impl SpawnSide {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SpawnSide::Random => "Aleatoire",
+            SpawnSide::Top => "Haut",
+            SpawnSide::Bottom => "Bas",
+            SpawnSide::Left => "Gauche",
+            SpawnSide::Right => "Droite",
+            SpawnSide::Point { .. } => "Point",
+        }
+    }
+
+    /// Following side in the editor's cycle
+    pub fn next(&self) -> Self {
+        match self {
+            SpawnSide::Random => SpawnSide::Top,
+            SpawnSide::Top => SpawnSide::Right,
+            SpawnSide::Right => SpawnSide::Bottom,
+            SpawnSide::Bottom => SpawnSide::Left,
+            SpawnSide::Left | SpawnSide::Point { .. } => SpawnSide::Random,
+        }
+    }
+}
+
+/// Sides a whole wave comes from. Only groups left on `SpawnSide::Random`
+/// follow it; groups with an explicit side keep theirs.
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum SpawnPattern {
+    /// Every enemy picks its own side
+    #[default]
+    Scattered,
+    /// A single side, rolled per wave
+    OneSide,
+    /// Two different sides, rolled per wave
+    TwoSides,
+    /// Two opposite sides
+    Pincer,
+    /// All four sides in turn
+    Encircle,
+}
+
+impl SpawnPattern {
+    const EDGES: [SpawnSide; 4] = [
+        SpawnSide::Top,
+        SpawnSide::Right,
+        SpawnSide::Bottom,
+        SpawnSide::Left,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SpawnPattern::Scattered => "Disperse",
+            SpawnPattern::OneSide => "Un cote",
+            SpawnPattern::TwoSides => "Deux cotes",
+            SpawnPattern::Pincer => "Tenaille",
+            SpawnPattern::Encircle => "Encerclement",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            SpawnPattern::Scattered => SpawnPattern::OneSide,
+            SpawnPattern::OneSide => SpawnPattern::TwoSides,
+            SpawnPattern::TwoSides => SpawnPattern::Pincer,
+            SpawnPattern::Pincer => SpawnPattern::Encircle,
+            SpawnPattern::Encircle => SpawnPattern::Scattered,
+        }
+    }
+
+    /// Sides used by one wave; empty when every enemy picks its own
+    pub fn roll_sides(&self, rng: &mut impl Rng) -> Vec<SpawnSide> {
+        let first = rng.gen_range(0..4);
+        match self {
+            SpawnPattern::Scattered => Vec::new(),
+            SpawnPattern::OneSide => vec![Self::EDGES[first]],
+            SpawnPattern::TwoSides => {
+                let second = (first + rng.gen_range(1..4)) % 4;
+                vec![Self::EDGES[first], Self::EDGES[second]]
+            }
+            SpawnPattern::Pincer => vec![Self::EDGES[first], Self::EDGES[(first + 2) % 4]],
+            SpawnPattern::Encircle => Self::EDGES.to_vec(),
+        }
+    }
+}
+
+/// Layout of a group around its spawn point
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum Formation {
+    /// Every enemy gets its own spawn point on the side
+    #[default]
+    Scattered,
+    /// Shoulder to shoulder, facing the core
+    Line { spacing: f32 },
+    /// Packed in a disc around a shared point
+    Cluster { radius: f32 },
+}
+
+impl Formation {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Formation::Scattered => "Dispersee",
+            Formation::Line { .. } => "Ligne",
+            Formation::Cluster { .. } => "Groupe",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            Formation::Scattered => Formation::Line { spacing: 30.0 },
+            Formation::Line { .. } => Formation::Cluster { radius: 40.0 },
+            Formation::Cluster { .. } => Formation::Scattered,
+        }
+    }
+}
+
+fn default_interval() -> f32 {
+    0.5
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SpawnGroup {
+    pub enemy: EnemyPick,
+    pub count: u32,
+    /// Seconds between two enemies of the group
+    #[serde(default = "default_interval")]
+    pub interval: f32,
+    /// Seconds after the wave start before the first enemy
+    #[serde(default)]
+    pub delay: f32,
+    #[serde(default)]
+    pub side: SpawnSide,
+    #[serde(default)]
+    pub formation: Formation,
+}
+
+impl SpawnGroup {
+    fn validate(&self) -> Result<(), String> {
+        validate_pick(&self.enemy)?;
+        if !(self.interval >= 0.0 && self.delay >= 0.0) {
+            return Err("intervalle ou delai negatif".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct WaveDef {
+    pub groups: Vec<SpawnGroup>,
+    #[serde(default)]
+    pub pattern: SpawnPattern,
+}
+
+/// Generator used past the last scripted wave
+#[derive(Clone, Serialize, Deserialize)]
+pub struct EndlessRules {
+    pub enemy: EnemyPick,
+    /// Enemy count is `base_count + count_per_wave * wave`
+    pub base_count: u32,
+    pub count_per_wave: u32,
+    #[serde(default = "default_interval")]
+    pub interval: f32,
+    pub boss: String,
+    /// Bosses come every `boss_every` waves, one more every ten waves
+    pub boss_every: u32,
+    /// Patterns rolled from for each generated wave; scattered if empty
+    #[serde(default)]
+    pub patterns: Vec<SpawnPattern>,
+}
+
+impl EndlessRules {
+    fn generate(&self, wave_number: u32, rng: &mut impl Rng) -> WaveDef {
+        let count = self.base_count + self.count_per_wave * wave_number;
+        let mut groups = vec![SpawnGroup {
+            enemy: self.enemy.clone(),
+            count,
+            interval: self.interval,
+            delay: 0.0,
+            side: SpawnSide::Random,
+            formation: Formation::Scattered,
+        }];
+        if self.boss_every > 0 && wave_number.is_multiple_of(self.boss_every) {
+            groups.push(SpawnGroup {
+                enemy: EnemyPick::Id(self.boss.clone()),
+                count: wave_number / 10 + 1,
+                interval: self.interval,
+                delay: count as f32 * self.interval,
+                side: SpawnSide::Random,
+                formation: Formation::Scattered,
+            });
+        }
+        let pattern = if self.patterns.is_empty() {
+            SpawnPattern::Scattered
+        } else {
+            self.patterns[rng.gen_range(0..self.patterns.len())]
+        };
+        WaveDef { groups, pattern }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct WaveScript {
+    /// Wave 1 is `waves[0]`
+    pub waves: Vec<WaveDef>,
+    pub endless: EndlessRules,
+}
+
+impl WaveScript {
+    /// Checks every wave and the endless rules only use known presets
+    fn validate(&self) -> Result<(), String> {
+        for (i, wave) in self.waves.iter().enumerate() {
+            for group in &wave.groups {
+                group
+                    .validate()
+                    .map_err(|e| format!("vague {}: {}", i + 1, e))?;
+            }
+        }
+        validate_pick(&self.endless.enemy)
+            .and_then(|()| validate_pick(&EnemyPick::Id(self.endless.boss.clone())))
+            .map_err(|e| format!("vagues sans fin: {}", e))
+    }
+
+    /// Groups of a wave: scripted while the script lasts, generated after
+    pub fn wave(&self, wave_number: u32, rng: &mut impl Rng) -> WaveDef {
+        match self.waves.get(wave_number.saturating_sub(1) as usize) {
+            Some(def) => def.clone(),
+            None => self.endless.generate(wave_number, rng),
+        }
+    }
+}
+
+impl WaveScript {
+    /// The script shipped with the game, also used by daily challenges so
+    /// everyone faces the same waves
+    pub fn builtin() -> Self {
+        serde_json::from_str(BUILTIN_WAVES).expect("built-in waves.json is valid")
+    }
+
+    /// ~/.sentinels/waves.json when it exists, the built-in script otherwise.
+    /// Read at the start of every run so saved edits apply. Errs with the
+    /// reason when the file doesn't parse or references unknown enemies.
+    pub fn load() -> Result<Self, String> {
+        let Ok(json) = std::fs::read_to_string(script_path()) else {
+            return Ok(Self::builtin());
+        };
+        serde_json::from_str::<WaveScript>(&json)
+            .map_err(|e| e.to_string())
+            .and_then(|script| script.validate().map(|()| script))
+            .map_err(|e| format!("waves.json: {}, vagues d'origine utilisees", e))
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        let path = script_path();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(std::io::Error::other)?;
+        std::fs::write(path, json)
+    }
+}
+
+fn script_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".sentinels")
+        .join("waves.json")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_script_only_uses_known_enemies() {
+        assert_eq!(WaveScript::builtin().validate(), Ok(()));
+    }
+}
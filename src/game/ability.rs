@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+
+/// How long the afterimage of a blink stays visible, in seconds
+pub const BLINK_TRAIL_DURATION: f32 = 0.4;
+
+/// Special behaviour attached to an enemy preset, run by the enemy tick
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum EnemyAbility {
+    /// Heals allies within `radius` by `hps` HP per second
+    Healer { radius: f32, hps: f32 },
+    /// Spawns `count` enemies of preset `into` where it dies
+    Splitter { into: String, count: u32 },
+    /// Absorbs the first `amount` damage (scaled with the wave like HP)
+    Shielded { amount: f32 },
+    /// Teleports `distance` toward the core every `interval` seconds
+    Blinker { distance: f32, interval: f32 },
+    /// Spawns `count` enemies of preset `enemy` around itself every `interval` seconds
+    Summoner {
+        enemy: String,
+        count: u32,
+        interval: f32,
+    },
+}
+
+impl EnemyAbility {
+    /// Preset spawned by this ability, checked once every preset is loaded
+    pub fn spawned_enemy(&self) -> Option<&str> {
+        match self {
+            EnemyAbility::Splitter { into, .. } => Some(into),
+            EnemyAbility::Summoner { enemy, .. } => Some(enemy),
+            _ => None,
+        }
+    }
+
+    /// Rejects values that would break the enemy tick, e.g. a zero interval
+    /// firing every frame
+    pub fn validate(&self) -> Result<(), String> {
+        let valid = match self {
+            EnemyAbility::Healer { radius, hps } => *radius > 0.0 && *hps >= 0.0,
+            EnemyAbility::Splitter { count, .. } => *count > 0,
+            EnemyAbility::Shielded { amount } => *amount >= 0.0,
+            EnemyAbility::Blinker { distance, interval } => *distance >= 0.0 && *interval > 0.0,
+            EnemyAbility::Summoner {
+                count, interval, ..
+            } => *count > 0 && *interval > 0.0,
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(format!("capacite invalide: {:?}", self))
+        }
+    }
+
+    fn interval(&self) -> f32 {
+        match self {
+            EnemyAbility::Blinker { interval, .. } | EnemyAbility::Summoner { interval, .. } => {
+                *interval
+            }
+            _ => 0.0,
+        }
+    }
+}
+
+/// An ability with its own cooldown
+#[derive(Clone)]
+pub struct AbilityState {
+    pub ability: EnemyAbility,
+    pub cooldown: f32,
+}
+
+impl AbilityState {
+    pub fn new(ability: EnemyAbility) -> Self {
+        Self {
+            cooldown: ability.interval(),
+            ability,
+        }
+    }
+
+    /// Ticks the cooldown; true when the ability is ready (the caller resets it)
+    pub fn tick(&mut self, dt: f32) -> bool {
+        self.cooldown -= dt;
+        self.cooldown <= 0.0
+    }
+
+    pub fn reset(&mut self) {
+        self.cooldown = self.ability.interval();
+    }
+}
@@ -0,0 +1,85 @@
+use gpui::Hsla;
+use rand::Rng;
+
+use super::elemental::TowerElement;
+
+/// Most affixes an elite can roll
+const MAX_AFFIXES: usize = 3;
+/// Share of max HP regenerated per second by `Regenerating`
+pub const REGEN_PER_SECOND: f32 = 0.02;
+/// Share of attack damage healed by `Vampiric` on each attack
+pub const VAMPIRIC_SHARE: f32 = 0.5;
+/// Share of damage taken sent back to the attacking tower by `Reflective`
+pub const REFLECT_SHARE: f32 = 0.2;
+/// Radius and tower damage of the `Explosive` death blast
+pub const EXPLOSION_RADIUS: f32 = 70.0;
+pub const EXPLOSION_DAMAGE: f32 = 30.0;
+
+/// Modifier rolled on late-wave enemies, making them elites
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum EliteAffix {
+    Fast,
+    Regenerating,
+    Vampiric,
+    Reflective,
+    ElementImmune(TowerElement),
+    Explosive,
+}
+
+impl EliteAffix {
+    /// Aura color drawn around the elite
+    pub fn color(&self) -> Hsla {
+        let (h, s, l) = match self {
+            EliteAffix::Fast => (0.15, 0.9, 0.6),
+            EliteAffix::Regenerating => (0.33, 0.8, 0.5),
+            EliteAffix::Vampiric => (0.95, 0.8, 0.4),
+            EliteAffix::Reflective => (0.55, 0.3, 0.8),
+            EliteAffix::ElementImmune(element) => {
+                let c = element.color();
+                (c.h, c.s, c.l)
+            }
+            EliteAffix::Explosive => (0.06, 1.0, 0.5),
+        };
+        Hsla { h, s, l, a: 0.35 }
+    }
+
+    fn random(rng: &mut impl Rng) -> Self {
+        match rng.gen_range(0..6) {
+            0 => EliteAffix::Fast,
+            1 => EliteAffix::Regenerating,
+            2 => EliteAffix::Vampiric,
+            3 => EliteAffix::Reflective,
+            4 => {
+                let elements = [
+                    TowerElement::Fire,
+                    TowerElement::Water,
+                    TowerElement::Electric,
+                    TowerElement::Earth,
+                ];
+                EliteAffix::ElementImmune(elements[rng.gen_range(0..elements.len())])
+            }
+            _ => EliteAffix::Explosive,
+        }
+    }
+}
+
+/// Chance for an enemy of this wave to be elite: none before wave 6, then
+/// +3% per wave up to 45%
+pub fn elite_chance(wave_number: u32) -> f32 {
+    (wave_number.saturating_sub(5) as f32 * 0.03).min(0.45)
+}
+
+/// Rolls the affixes of a freshly spawned enemy; empty for a regular one.
+/// Each extra affix needs another successful roll at half the chance.
+pub fn roll_affixes(wave_number: u32, rng: &mut impl Rng) -> Vec<EliteAffix> {
+    let mut chance = elite_chance(wave_number);
+    let mut affixes = Vec::new();
+    while affixes.len() < MAX_AFFIXES && rng.r#gen::<f32>() < chance {
+        let affix = EliteAffix::random(rng);
+        if !affixes.contains(&affix) {
+            affixes.push(affix);
+        }
+        chance /= 2.0;
+    }
+    affixes
+}
@@ -0,0 +1,49 @@
+use super::tower::TowerUpgradeId;
+use crate::data::blueprint::{Blueprint, BlueprintTower};
+use crate::data::tower_defs::TowerKind;
+
+/// A blueprint tower during a run, linked to the tower built from it
+#[derive(Clone)]
+pub struct BlueprintGhost {
+    pub plan: BlueprintTower,
+    pub tower_id: Option<usize>,
+}
+
+#[derive(Clone)]
+pub struct ActiveBlueprint {
+    pub name: String,
+    pub ghosts: Vec<BlueprintGhost>,
+    /// Buy placements and upgrades automatically as gold allows
+    pub auto_build: bool,
+}
+
+impl ActiveBlueprint {
+    pub fn new(blueprint: &Blueprint) -> Self {
+        Self {
+            name: blueprint.name.clone(),
+            ghosts: blueprint
+                .towers
+                .iter()
+                .map(|plan| BlueprintGhost {
+                    plan: plan.clone(),
+                    tower_id: None,
+                })
+                .collect(),
+            auto_build: false,
+        }
+    }
+}
+
+/// Next purchase needed to bring a ghost in line with its plan
+#[derive(Clone, Copy)]
+pub enum BlueprintPurchase {
+    Place {
+        kind: TowerKind,
+        x: f32,
+        y: f32,
+    },
+    Upgrade {
+        tower_idx: usize,
+        upgrade_id: TowerUpgradeId,
+    },
+}
@@ -0,0 +1,65 @@
+use super::elemental::TowerElement;
+use crate::data::boss_defs::{BossDef, BossPhase};
+
+/// Runtime state of a scripted boss: active phase, its timer and any immunity
+#[derive(Clone)]
+pub struct BossState {
+    pub def: &'static BossDef,
+    /// Index of the active phase, None until the first threshold is crossed
+    pub phase: Option<usize>,
+    pub phase_timer: f32,
+    pub immunity: Option<(TowerElement, f32)>,
+}
+
+impl BossState {
+    pub fn new(def: &'static BossDef) -> Self {
+        Self {
+            def,
+            phase: None,
+            phase_timer: 0.0,
+            immunity: None,
+        }
+    }
+
+    pub fn active_phase(&self) -> Option<&'static BossPhase> {
+        self.phase.map(|i| &self.def.phases[i])
+    }
+
+    /// Moves to the deepest phase reached at this HP ratio; returns it when it changed
+    pub fn advance_phase(&mut self, hp_ratio: f32) -> Option<&'static BossPhase> {
+        let reached = self
+            .def
+            .phases
+            .iter()
+            .rposition(|p| hp_ratio <= p.hp_threshold)?;
+        if self.phase.is_some_and(|current| current >= reached) {
+            return None;
+        }
+        self.phase = Some(reached);
+        self.phase_timer = 0.0;
+        Some(&self.def.phases[reached])
+    }
+
+    /// Ticks the phase timer; true every `interval` seconds
+    pub fn tick_phase_timer(&mut self, dt: f32, interval: f32) -> bool {
+        self.phase_timer -= dt;
+        if self.phase_timer <= 0.0 {
+            self.phase_timer = interval;
+            return true;
+        }
+        false
+    }
+
+    pub fn tick_immunity(&mut self, dt: f32) {
+        if let Some((_, ref mut remaining)) = self.immunity {
+            *remaining -= dt;
+            if *remaining <= 0.0 {
+                self.immunity = None;
+            }
+        }
+    }
+
+    pub fn is_immune_to(&self, element: TowerElement) -> bool {
+        self.immunity.is_some_and(|(immune, _)| immune == element)
+    }
+}
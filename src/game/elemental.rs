@@ -56,7 +56,7 @@ impl TowerElement {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub enum ElementalReaction {
     Steam,
     Overload,
@@ -84,4 +84,15 @@ impl ElementalReaction {
             _ => None,
         }
     }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Steam => "Vapeur",
+            Self::Overload => "Surcharge",
+            Self::Magma => "Magma",
+            Self::Conductor => "Conducteur",
+            Self::Erosion => "Erosion",
+            Self::Magnetic => "Magnetique",
+        }
+    }
 }
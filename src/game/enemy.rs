@@ -1,28 +1,13 @@
-use super::elemental::TowerElement;
+use serde::{Deserialize, Serialize};
+
+use super::ability::{AbilityState, BLINK_TRAIL_DURATION, EnemyAbility};
+use super::affix::{EliteAffix, REGEN_PER_SECOND, VAMPIRIC_SHARE};
+use super::boss::BossState;
+use super::elemental::{ElementalReaction, TowerElement};
 use super::{Point2D, Projectile, ProjectileSource};
+use crate::data::enemy_types::{EnemyPreset, EnemyVisual};
 use crate::data::tower_defs::{EffectTarget, ResolvedAction, ResolvedDamage};
 
-#[derive(Clone, Copy, PartialEq)]
-pub enum EnemyShape {
-    Triangle,
-    Square,
-    Pentagon,
-    Hexagon,
-    Octagon,
-}
-
-impl EnemyShape {
-    pub fn sides(&self) -> u32 {
-        match self {
-            EnemyShape::Triangle => 3,
-            EnemyShape::Square => 4,
-            EnemyShape::Pentagon => 5,
-            EnemyShape::Hexagon => 6,
-            EnemyShape::Octagon => 8,
-        }
-    }
-}
-
 #[derive(Clone)]
 pub struct AppliedElement {
     pub element: TowerElement,
@@ -33,13 +18,74 @@ pub struct AppliedElement {
 pub struct BurnState {
     pub dps: f32,
     pub remaining: f32,
+    pub source: ProjectileSource,
+    pub element: TowerElement,
+}
+
+/// How an enemy's armor reduces incoming hits
+#[derive(Clone, Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub enum ArmorKind {
+    /// Armor points are subtracted from each hit
+    Flat,
+    /// Armor is the share of each hit blocked
+    Percent,
+}
+
+/// Flat armor can never reduce a hit below this share of its raw damage
+const MIN_DAMAGE_SHARE: f32 = 0.2;
+/// Upper bound for percentage armor
+const MAX_PERCENT_ARMOR: f32 = 0.75;
+/// Share of armor removed each time Erosion triggers
+const EROSION_ARMOR_SHRED: f32 = 0.5;
+
+/// Result of a single damage application, used for stats attribution
+#[derive(Clone, Copy)]
+pub struct DamageOutcome {
+    /// Damage of the hit before armor
+    pub raw: f32,
+    /// Damage blocked by armor
+    pub mitigated: f32,
+    /// Damage that actually removed HP
+    pub dealt: f32,
+    /// Damage in excess of the HP the enemy had left
+    pub overkill: f32,
+    pub reaction: Option<ElementalReaction>,
+    /// True if this hit brought the enemy from alive to dead
+    pub killed: bool,
+}
+
+impl DamageOutcome {
+    fn from_hp(hp_before: f32, hp_after: f32, reaction: Option<ElementalReaction>) -> Self {
+        // A corpse hit before its removal (AoE, chain) takes nothing more
+        if hp_before <= 0.0 {
+            return Self {
+                raw: 0.0,
+                mitigated: 0.0,
+                dealt: 0.0,
+                overkill: 0.0,
+                reaction: None,
+                killed: false,
+            };
+        }
+        let total = hp_before - hp_after;
+        let dealt = total.min(hp_before.max(0.0)).max(0.0);
+        Self {
+            raw: total,
+            mitigated: 0.0,
+            dealt,
+            overkill: total - dealt,
+            reaction,
+            killed: hp_before > 0.0 && hp_after <= 0.0,
+        }
+    }
 }
 
 #[derive(Clone)]
 pub struct Enemy {
     pub id: usize,
+    pub name: String,
     pub position: Point2D,
-    pub shape: EnemyShape,
+    pub visual: EnemyVisual,
     pub hp: f32,
     pub max_hp: f32,
     pub speed: f32,
@@ -54,21 +100,49 @@ pub struct Enemy {
     pub slow_duration: f32,
     pub stun_duration: f32,
     pub is_boss: bool,
+    /// Phase script of boss enemies
+    pub boss: Option<BossState>,
+    pub targets_towers: bool,
+    pub abilities: Vec<AbilityState>,
+    /// Remaining damage absorbed by the `Shielded` ability
+    pub absorb: f32,
+    pub max_absorb: f32,
+    /// Position left by the last blink and its remaining display time
+    pub blink_trail: Option<(Point2D, f32)>,
+    pub armor_kind: ArmorKind,
+    pub armor: f32,
+    /// Elite affixes, empty for regular enemies
+    pub affixes: Vec<EliteAffix>,
+    /// Share of max HP regenerated per second
+    pub regen: f32,
     pub burn: Option<BurnState>,
+    /// Source of the killing blow, for gold attribution
+    pub killed_by: Option<ProjectileSource>,
 }
 
 impl Enemy {
-    pub fn new(id: usize, shape: EnemyShape, wave_number: u32, spawn_pos: Point2D) -> Self {
-        use crate::data::enemy_types::get_preset;
+    pub fn new(id: usize, preset: &EnemyPreset, wave_number: u32, spawn_pos: Point2D) -> Self {
+        use crate::data::boss_defs::boss_for_wave;
 
-        let preset = get_preset(shape);
-        let hp_scale = 1.0 + 0.04 * wave_number as f32;
-        let damage_scale = 1.0 + 0.02 * wave_number as f32;
+        let is_boss = preset.is_boss;
+        let boss = is_boss.then(|| BossState::new(boss_for_wave(wave_number)));
+        let hp_scale = preset.hp_scaling.factor(wave_number)
+            * boss.as_ref().map_or(1.0, |b| b.def.hp_multiplier);
+        let damage_scale = preset.damage_scaling.factor(wave_number);
+        let absorb = preset
+            .abilities
+            .iter()
+            .map(|ability| match ability {
+                EnemyAbility::Shielded { amount } => amount * hp_scale,
+                _ => 0.0,
+            })
+            .sum();
 
         Self {
             id,
+            name: preset.name.clone(),
             position: spawn_pos,
-            shape,
+            visual: preset.visual,
             hp: preset.base_hp * hp_scale,
             max_hp: preset.base_hp * hp_scale,
             speed: preset.base_speed,
@@ -82,12 +156,60 @@ impl Enemy {
             slow_factor: 1.0,
             slow_duration: 0.0,
             stun_duration: 0.0,
-            is_boss: shape == EnemyShape::Octagon,
+            is_boss,
+            boss,
+            targets_towers: preset.targets_towers,
+            // Scripted bosses summon through their phases instead
+            abilities: preset
+                .abilities
+                .iter()
+                .filter(|a| !(is_boss && matches!(a, EnemyAbility::Summoner { .. })))
+                .map(|a| AbilityState::new(a.clone()))
+                .collect(),
+            absorb,
+            max_absorb: absorb,
+            blink_trail: None,
+            armor_kind: preset.armor_kind,
+            armor: preset.armor + preset.armor_per_wave * wave_number as f32,
+            affixes: Vec::new(),
+            regen: 0.0,
             burn: None,
+            killed_by: None,
+        }
+    }
+
+    /// Turns the enemy into an elite: +50% HP and +100% gold per affix, plus
+    /// the stat change of each affix
+    pub fn make_elite(&mut self, affixes: Vec<EliteAffix>) {
+        let count = affixes.len() as f32;
+        self.max_hp *= 1.0 + 0.5 * count;
+        self.hp = self.max_hp;
+        self.gold_value *= 1 + affixes.len() as u32;
+        if affixes.contains(&EliteAffix::Fast) {
+            self.speed *= 1.5;
         }
+        if affixes.contains(&EliteAffix::Regenerating) {
+            self.regen += REGEN_PER_SECOND;
+        }
+        self.affixes = affixes;
+    }
+
+    pub fn has_affix(&self, affix: EliteAffix) -> bool {
+        self.affixes.contains(&affix)
+    }
+
+    pub fn is_immune_to(&self, element: TowerElement) -> bool {
+        self.boss.as_ref().is_some_and(|b| b.is_immune_to(element))
+            || self.has_affix(EliteAffix::ElementImmune(element))
     }
 
-    pub fn tick(&mut self, dt: f32, center: &Point2D, shield_radius: Option<f32>) {
+    /// Returns the burn damage dealt this tick, if any, with its source
+    pub fn tick(
+        &mut self,
+        dt: f32,
+        center: &Point2D,
+        shield_radius: Option<f32>,
+    ) -> Option<(ProjectileSource, TowerElement, DamageOutcome)> {
         // Tick stun
         if self.stun_duration > 0.0 {
             self.stun_duration -= dt;
@@ -97,10 +219,7 @@ impl Enemy {
 
         // Movement (blocked by stun)
         if !is_stunned {
-            let stop_distance = match shield_radius {
-                Some(r) => r,
-                None => self.attack_range,
-            };
+            let stop_distance = self.stop_distance(shield_radius);
 
             let dist_to_center = self.position.distance_to(center);
             if dist_to_center > stop_distance {
@@ -114,6 +233,32 @@ impl Enemy {
                     self.position.y += dy / dist * effective_speed;
                 }
             }
+
+            // Blink: short teleport toward the core, never past the stop distance
+            for state in &mut self.abilities {
+                let EnemyAbility::Blinker { distance, .. } = state.ability else {
+                    continue;
+                };
+                if !state.tick(dt) {
+                    continue;
+                }
+                state.reset();
+                let dist_to_center = self.position.distance_to(center);
+                let jump = distance.min(dist_to_center - stop_distance);
+                if jump > 0.0 {
+                    let from = self.position.clone();
+                    self.position.x += (center.x - from.x) / dist_to_center * jump;
+                    self.position.y += (center.y - from.y) / dist_to_center * jump;
+                    self.blink_trail = Some((from, BLINK_TRAIL_DURATION));
+                }
+            }
+        }
+
+        if let Some((_, ref mut remaining)) = self.blink_trail {
+            *remaining -= dt;
+            if *remaining <= 0.0 {
+                self.blink_trail = None;
+            }
         }
 
         // Tick slow duration
@@ -124,20 +269,48 @@ impl Enemy {
             }
         }
 
-        // Tick burn damage
+        // Tick burn damage; it keeps running out while the enemy is immune
+        let mut burn_tick = None;
+        let burn_immune = self
+            .burn
+            .as_ref()
+            .is_some_and(|burn| self.is_immune_to(burn.element));
         if let Some(ref mut burn) = self.burn {
-            self.hp -= burn.dps * dt;
+            if !burn_immune {
+                let hp_before = self.hp;
+                self.hp -= burn.dps * dt;
+                let outcome = DamageOutcome::from_hp(hp_before, self.hp, None);
+                if outcome.killed {
+                    self.killed_by = Some(burn.source);
+                }
+                burn_tick = Some((burn.source, burn.element, outcome));
+            }
             burn.remaining -= dt;
             if burn.remaining <= 0.0 {
                 self.burn = None;
             }
         }
 
+        if self.regen > 0.0 {
+            self.heal(self.max_hp * self.regen * dt);
+        }
+
         // Tick applied elements
         self.applied_elements.retain_mut(|ae| {
             ae.duration -= dt;
             ae.duration > 0.0
         });
+
+        burn_tick
+    }
+
+    /// Distance from the center where the enemy stops walking: the shield
+    /// edge if active, otherwise its attack range
+    pub fn stop_distance(&self, shield_radius: Option<f32>) -> f32 {
+        match shield_radius {
+            Some(r) => r,
+            None => self.attack_range,
+        }
     }
 
     pub fn try_attack(
@@ -158,6 +331,9 @@ impl Enemy {
         self.attack_cooldown -= dt;
         if self.attack_cooldown <= 0.0 {
             self.attack_cooldown = 1.0 / self.attack_speed;
+            if self.has_affix(EliteAffix::Vampiric) {
+                self.heal(self.damage * VAMPIRIC_SHARE);
+            }
             Some(Projectile {
                 origin: self.position.clone(),
                 target_pos: target_pos.clone(),
@@ -168,9 +344,13 @@ impl Enemy {
                 actions: vec![ResolvedAction::ApplyDamage {
                     target: EffectTarget::Single,
                     damage: ResolvedDamage::Fixed(self.damage),
+                    armor_pierce: 0.0,
                 }],
                 lifetime: 3.0,
                 target_enemy_id: None,
+                action_kind: None,
+                target_tower_id: None,
+                shooter: Some(self.display_name().to_string()),
                 fade_timer: None,
                 size: 3.0,
             })
@@ -179,8 +359,39 @@ impl Enemy {
         }
     }
 
-    pub fn take_damage(&mut self, damage: f32, element: TowerElement) {
-        self.hp -= damage;
+    /// Damage left after armor, with `armor_pierce` the share of armor ignored
+    pub fn mitigate(&self, raw: f32, armor_pierce: f32) -> f32 {
+        let armor = self.armor * (1.0 - armor_pierce.clamp(0.0, 1.0));
+        match self.armor_kind {
+            ArmorKind::Flat => (raw - armor).max(raw * MIN_DAMAGE_SHARE),
+            ArmorKind::Percent => raw * (1.0 - armor.min(MAX_PERCENT_ARMOR)),
+        }
+    }
+
+    /// Applies a hit through armor, the `Shielded` absorption and elemental
+    /// reactions. Burn ticks bypass this and ignore armor.
+    pub fn take_damage(
+        &mut self,
+        raw_damage: f32,
+        element: TowerElement,
+        armor_pierce: f32,
+    ) -> DamageOutcome {
+        let hp_before = self.hp;
+        let mut triggered = None;
+        // A corpse hit before its removal (AoE, chain) shows no damage number
+        if self.is_dead() {
+            return DamageOutcome::from_hp(hp_before, hp_before, None);
+        }
+        if self.is_immune_to(element) {
+            return DamageOutcome {
+                mitigated: raw_damage,
+                ..DamageOutcome::from_hp(hp_before, hp_before, None)
+            };
+        }
+        let damage = self.mitigate(raw_damage, armor_pierce);
+        let absorbed = damage.min(self.absorb);
+        self.absorb -= absorbed;
+        self.hp -= damage - absorbed;
 
         if element != TowerElement::Neutral {
             // Check for elemental reactions with existing elements
@@ -219,6 +430,7 @@ impl Enemy {
                     super::elemental::ElementalReaction::Erosion => {
                         self.slow_factor = 0.3;
                         self.slow_duration = 3.0;
+                        self.armor *= 1.0 - EROSION_ARMOR_SHRED;
                     }
                     super::elemental::ElementalReaction::Magnetic => {
                         self.slow_factor = 0.0;
@@ -227,8 +439,60 @@ impl Enemy {
                 }
                 // Consume elements on reaction
                 self.applied_elements.clear();
+                triggered = Some(reaction);
+            }
+        }
+
+        DamageOutcome {
+            raw: raw_damage,
+            mitigated: raw_damage - damage,
+            ..DamageOutcome::from_hp(hp_before, self.hp, triggered)
+        }
+    }
+
+    /// Dead enemies stay dead: their kill is already counted
+    pub fn heal(&mut self, amount: f32) {
+        if self.is_dead() {
+            return;
+        }
+        self.hp = (self.hp + amount).min(self.max_hp);
+    }
+
+    /// Radius and HP per second of the `Healer` ability, if any
+    pub fn heal_aura(&self) -> Option<(f32, f32)> {
+        self.abilities.iter().find_map(|state| match state.ability {
+            EnemyAbility::Healer { radius, hps } => Some((radius, hps)),
+            _ => None,
+        })
+    }
+
+    /// Preset id and count spawned on death by the `Splitter` ability, if any
+    pub fn split_into(&self) -> Option<(String, u32)> {
+        self.abilities
+            .iter()
+            .find_map(|state| match &state.ability {
+                EnemyAbility::Splitter { into, count } => Some((into.clone(), *count)),
+                _ => None,
+            })
+    }
+
+    /// Ticks `Summoner` cooldowns and returns what to spawn this tick
+    pub fn tick_summons(&mut self, dt: f32) -> Vec<(String, u32)> {
+        if self.stun_duration > 0.0 {
+            return Vec::new();
+        }
+        let mut summons = Vec::new();
+        for state in &mut self.abilities {
+            let EnemyAbility::Summoner { enemy, count, .. } = &state.ability else {
+                continue;
+            };
+            let summon = (enemy.clone(), *count);
+            if state.tick(dt) {
+                state.reset();
+                summons.push(summon);
             }
         }
+        summons
     }
 
     pub fn apply_slow(&mut self, ratio: f32, duration: f32) {
@@ -243,15 +507,25 @@ impl Enemy {
         self.stun_duration = self.stun_duration.max(duration);
     }
 
-    pub fn apply_burn(&mut self, dps: f32, duration: f32) {
-        // Refresh burn with the strongest values
+    pub fn apply_burn(
+        &mut self,
+        dps: f32,
+        duration: f32,
+        source: ProjectileSource,
+        element: TowerElement,
+    ) {
+        // Refresh burn with the strongest values; the latest applier gets the credit
         if let Some(ref mut burn) = self.burn {
             burn.dps = burn.dps.max(dps);
             burn.remaining = burn.remaining.max(duration);
+            burn.source = source;
+            burn.element = element;
         } else {
             self.burn = Some(BurnState {
                 dps,
                 remaining: duration,
+                source,
+                element,
             });
         }
     }
@@ -259,4 +533,11 @@ impl Enemy {
     pub fn is_dead(&self) -> bool {
         self.hp <= 0.0
     }
+
+    /// Boss name for bosses, preset name otherwise
+    pub fn display_name(&self) -> &str {
+        self.boss
+            .as_ref()
+            .map_or(self.name.as_str(), |b| b.def.name)
+    }
 }
@@ -0,0 +1,56 @@
+use crate::data::achievements::Achievement;
+
+/// How long an event stays in the HUD feed, in seconds
+pub const EVENT_DURATION: f32 = 4.0;
+
+#[derive(Clone)]
+pub enum GameEventKind {
+    TowerDestroyed {
+        name: &'static str,
+    },
+    BossSpawned {
+        name: &'static str,
+    },
+    BossPhase {
+        boss: &'static str,
+        phase: &'static str,
+    },
+    EarlyCall {
+        gold: u32,
+    },
+    Interest {
+        gold: u32,
+    },
+    AchievementUnlocked {
+        achievement: Achievement,
+    },
+}
+
+/// Something notable that happened during the run, shown briefly in the HUD
+#[derive(Clone)]
+pub struct GameEvent {
+    pub kind: GameEventKind,
+    pub lifetime: f32,
+}
+
+impl GameEvent {
+    pub fn new(kind: GameEventKind) -> Self {
+        Self {
+            kind,
+            lifetime: EVENT_DURATION,
+        }
+    }
+
+    pub fn message(&self) -> String {
+        match &self.kind {
+            GameEventKind::TowerDestroyed { name } => format!("{} detruite !", name),
+            GameEventKind::BossSpawned { name } => format!("{} apparait !", name),
+            GameEventKind::BossPhase { boss, phase } => format!("{} : {}", boss, phase),
+            GameEventKind::EarlyCall { gold } => format!("Vague lancee en avance : +{} or", gold),
+            GameEventKind::Interest { gold } => format!("Interets : +{} or", gold),
+            GameEventKind::AchievementUnlocked { achievement } => {
+                format!("Succes debloque : {}", achievement.name())
+            }
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use super::Point2D;
+use super::tower::{Tower, TowerUpgradeId};
+
+/// An economy action taken during the current preparation phase
+#[derive(Clone)]
+pub enum EconomyAction {
+    Place {
+        tower_id: usize,
+        cost: u32,
+        /// Sentinelles-only achievement flag before the placement
+        only_sentinelles: bool,
+    },
+    Upgrade {
+        tower_id: usize,
+        upgrade_id: TowerUpgradeId,
+        cost: u32,
+    },
+    Sell {
+        tower: Tower,
+        index: usize,
+        refund: u32,
+    },
+    Move {
+        tower_id: usize,
+        from: Point2D,
+        cost: u32,
+    },
+}
+
+impl EconomyAction {
+    pub fn label(&self) -> &'static str {
+        match self {
+            EconomyAction::Place { .. } => "pose",
+            EconomyAction::Upgrade { .. } => "amelioration",
+            EconomyAction::Sell { .. } => "vente",
+            EconomyAction::Move { .. } => "deplacement",
+        }
+    }
+}
+
+/// Undo stack for economy actions, only filled while preparing and cleared
+/// whenever the phase changes
+#[derive(Clone, Default)]
+pub struct EconomyHistory {
+    actions: Vec<EconomyAction>,
+}
+
+impl EconomyHistory {
+    pub fn push(&mut self, action: EconomyAction) {
+        self.actions.push(action);
+    }
+
+    pub fn pop(&mut self) -> Option<EconomyAction> {
+        self.actions.pop()
+    }
+
+    pub fn last(&self) -> Option<&EconomyAction> {
+        self.actions.last()
+    }
+
+    pub fn len(&self) -> usize {
+        self.actions.len()
+    }
+
+    /// Drops the actions pushed after the stack had `len` entries
+    pub fn truncate(&mut self, len: usize) {
+        self.actions.truncate(len);
+    }
+
+    pub fn clear(&mut self) {
+        self.actions.clear();
+    }
+}
@@ -1,19 +1,45 @@
+pub mod ability;
+pub mod affix;
+pub mod blueprint;
+pub mod boss;
 pub mod elemental;
 pub mod enemy;
+pub mod event;
+pub mod history;
+pub mod modifiers;
 pub mod player;
+pub mod selection;
 pub mod skill_tree;
+pub mod stats;
+pub mod steering;
 pub mod tower;
 pub mod wave;
 
 use crate::data::SaveData;
+use crate::data::achievements::{
+    Achievement, NO_SHIELD_WAVE, OLD_GUARD_WAVE, STEAM_REACTIONS_GOAL, SURVIVOR_WAVE,
+};
+use crate::data::blueprint::{Blueprint, BlueprintTower};
+use crate::data::boss_defs::BossPhaseKind;
+use crate::data::daily::DailyChallenge;
+use crate::data::difficulty::RunSettings;
+use crate::data::run_history::RunRecord;
 use crate::data::tower_defs::{
     EffectTarget, ResolvedAction, ResolvedDamage, ResolvedEffect, TowerKind, get_def,
 };
-use elemental::TowerElement;
+use crate::data::wave_script::WaveScript;
+use affix::{EXPLOSION_DAMAGE, EXPLOSION_RADIUS, EliteAffix, REFLECT_SHARE};
+use blueprint::{ActiveBlueprint, BlueprintPurchase};
+use elemental::{ElementalReaction, TowerElement};
 use enemy::Enemy;
+use event::{GameEvent, GameEventKind};
+use history::{EconomyAction, EconomyHistory};
+use modifiers::RunModifiers;
 use player::Player;
+use selection::{SelectionUpgrade, TowerSelection};
+use stats::{ActionKind, RunStats};
 use std::process::Command;
-use tower::Tower;
+use tower::{TargetingMode, Tower};
 use wave::WaveManager;
 
 fn send_notification(title: &str, message: &str) {
@@ -71,6 +97,13 @@ pub struct Projectile {
     pub actions: Vec<ResolvedAction>,
     pub lifetime: f32,
     pub target_enemy_id: Option<usize>,
+    /// Action credited with its damage in the stats instead of the one
+    /// derived from each action's target, for chain hops
+    pub action_kind: Option<ActionKind>,
+    /// Tower aimed at by an enemy projectile, None when it targets the core
+    pub target_tower_id: Option<usize>,
+    /// Name of the enemy that fired it, still known once the shooter has died
+    pub shooter: Option<String>,
     /// Fade-out timer: None = active, Some(remaining) = fading out
     pub fade_timer: Option<f32>,
     /// Projectile visual size (radius of head, affects trail width)
@@ -79,6 +112,20 @@ pub struct Projectile {
 
 pub const PROJECTILE_FADE_DURATION: f32 = 0.15;
 
+/// Share of a tower's invested gold given back when selling it
+pub const DEFAULT_REFUND_RATIO: f32 = 0.5;
+
+/// Projectile hit on an enemy, applied once every projectile has moved:
+/// enemy index, actions, element, impact point, source and credited action
+type EnemyHit = (
+    usize,
+    Vec<ResolvedAction>,
+    TowerElement,
+    Point2D,
+    ProjectileSource,
+    Option<ActionKind>,
+);
+
 #[derive(Clone)]
 pub struct Shield {
     pub active: bool,
@@ -129,6 +176,29 @@ pub struct AoeSplash {
     pub max_lifetime: f32,
 }
 
+/// Floating number over a hit enemy; `raw` differs from `amount` when armor blocked part of it
+#[derive(Clone)]
+pub struct DamageNumber {
+    pub position: Point2D,
+    pub amount: f32,
+    pub raw: f32,
+    pub lifetime: f32,
+}
+
+pub const DAMAGE_NUMBER_DURATION: f32 = 0.8;
+/// Oldest numbers are dropped past this count to keep the overlay light
+const MAX_DAMAGE_NUMBERS: usize = 40;
+
+/// Floating "+N" over the player for gold earned outside kills, e.g. interest
+#[derive(Clone)]
+pub struct GoldNumber {
+    pub position: Point2D,
+    pub amount: u32,
+    pub lifetime: f32,
+}
+
+pub const GOLD_NUMBER_DURATION: f32 = 1.5;
+
 #[derive(Clone)]
 pub struct GoldPulse {
     pub position: Point2D,
@@ -144,6 +214,8 @@ pub struct Economy {
     pub score: u32,
     pub wave_number: u32,
     pub pepites: u32,
+    /// Gold received during the run, refunds left out
+    pub gold_earned: u32,
 }
 
 pub struct GameState {
@@ -155,49 +227,95 @@ pub struct GameState {
     pub projectiles: Vec<Projectile>,
     pub aoe_splashes: Vec<AoeSplash>,
     pub gold_pulses: Vec<GoldPulse>,
+    pub damage_numbers: Vec<DamageNumber>,
+    pub gold_numbers: Vec<GoldNumber>,
+    pub events: Vec<GameEvent>,
     pub wave_manager: WaveManager,
     pub economy: Economy,
     pub phase: GamePhase,
     pub viewport_size: (f32, f32),
     pub placement_mode: Option<TowerKind>,
     pub move_mode: Option<usize>,
-    pub selected_tower: Option<usize>,
+    pub selection: TowerSelection,
     pub elapsed: f32,
     pub save_data: SaveData,
     pub speed_multiplier: f32,
+    pub refund_ratio: f32,
+    pub stats: RunStats,
+    pub blueprint: Option<ActiveBlueprint>,
+    pub history: EconomyHistory,
+    next_tower_id: usize,
+    /// Damage sent back to towers by reflective elites, applied after hits
+    reflected_hits: Vec<(usize, f32)>,
+    pub settings: RunSettings,
+    pub daily: Option<DailyChallenge>,
+    /// First attempt at today's challenge, whose result is recorded
+    pub daily_ranked: bool,
+    pub modifiers: RunModifiers,
+    /// No tower other than a Sentinelle was built this run
+    only_sentinelles: bool,
+    towers_built: Vec<(TowerKind, u32)>,
+    /// Enemy that last hurt the core, reported as the cause of death
+    last_hit_by: Option<String>,
 }
 
 impl GameState {
-    pub fn new(save_data: &SaveData) -> Self {
-        let bonus_gold = save_data.get_upgrade_level("bonus_gold") as u32 * 50;
-        let shield_level = save_data.get_upgrade_level("shield");
-        let tower_slots_level = save_data.get_upgrade_level("tower_slots");
-
-        Self {
-            player: Player::new(),
-            shield: Shield::new(shield_level),
+    pub fn new(save_data: &SaveData, settings: RunSettings, script: WaveScript) -> Self {
+        let mut save_data = save_data.clone();
+        let daily = settings.daily.map(DailyChallenge::for_day);
+        let daily_ranked = daily
+            .as_ref()
+            .is_some_and(|challenge| save_data.begin_daily(challenge.day));
+        let modifiers = RunModifiers::new(&save_data, &settings);
+        let mut player = Player::new();
+        modifiers.apply_to_player(&mut player);
+
+        let mut state = Self {
+            player,
+            shield: Shield::new(modifiers.shield_level),
             towers: Vec::new(),
-            max_towers: 5 + tower_slots_level,
+            max_towers: 5 + modifiers.bonus_tower_slots,
             enemies: Vec::new(),
             projectiles: Vec::new(),
             aoe_splashes: Vec::new(),
             gold_pulses: Vec::new(),
-            wave_manager: WaveManager::new(),
+            damage_numbers: Vec::new(),
+            gold_numbers: Vec::new(),
+            events: Vec::new(),
+            wave_manager: WaveManager::new(&settings, daily.as_ref().map(|c| c.seed), script),
             economy: Economy {
-                gold: 500 + bonus_gold,
+                gold: modifiers.starting_gold,
                 score: 0,
                 wave_number: 0,
                 pepites: 0,
+                gold_earned: 0,
             },
             phase: GamePhase::Preparing,
             viewport_size: (1200.0, 800.0),
             placement_mode: None,
             move_mode: None,
-            selected_tower: None,
+            selection: TowerSelection::default(),
             elapsed: 0.0,
-            save_data: save_data.clone(),
+            save_data,
             speed_multiplier: 1.0,
+            refund_ratio: DEFAULT_REFUND_RATIO,
+            stats: RunStats::new(),
+            blueprint: None,
+            history: EconomyHistory::default(),
+            next_tower_id: 0,
+            reflected_hits: Vec::new(),
+            settings,
+            daily,
+            daily_ranked,
+            modifiers,
+            only_sentinelles: true,
+            towers_built: Vec::new(),
+            last_hit_by: None,
+        };
+        if let Some(wave) = state.settings.test_wave {
+            state.wave_manager.skip_to_wave(wave);
         }
+        state
     }
 
     pub fn tick(&mut self, dt: f32) {
@@ -210,18 +328,47 @@ impl GameState {
         // 1. Wave manager update
         if self.phase == GamePhase::Active {
             let new_enemies = self.wave_manager.tick(dt, self.viewport_size);
+            for enemy in &new_enemies {
+                if let Some(boss) = &enemy.boss {
+                    self.events.push(GameEvent::new(GameEventKind::BossSpawned {
+                        name: boss.def.name,
+                    }));
+                }
+            }
             self.enemies.extend(new_enemies);
 
             // Check wave completion
             if self.wave_manager.is_wave_complete() && self.enemies.is_empty() {
                 self.phase = GamePhase::Preparing;
-                self.economy.gold += 100 + self.wave_manager.current_wave * 50;
+                self.history.clear();
+                // Interest is paid on the gold banked during the wave, before the bonus
+                let interest = self.modifiers.interest(self.economy.gold);
+                let income = 100 + self.wave_manager.current_wave * 50 + interest;
+                self.economy.gold += income;
+                self.economy.gold_earned += income;
+                if interest > 0 {
+                    self.events
+                        .push(GameEvent::new(GameEventKind::Interest { gold: interest }));
+                    self.gold_pulses.push(GoldPulse {
+                        position: self.player.position.clone(),
+                        radius: 0.0,
+                        max_radius: 80.0,
+                        lifetime: 0.8,
+                        max_lifetime: 0.8,
+                    });
+                    self.gold_numbers.push(GoldNumber {
+                        position: self.player.position.clone(),
+                        amount: interest,
+                        lifetime: GOLD_NUMBER_DURATION,
+                    });
+                }
                 self.wave_manager.start_between_waves();
             }
         } else if self.phase == GamePhase::Preparing {
-            if self.wave_manager.tick_between_waves(dt) {
+            if self.wave_manager.tick_between_waves(dt, self.viewport_size) {
                 self.wave_manager.start_next_wave();
                 self.phase = GamePhase::Active;
+                self.history.clear();
                 self.economy.wave_number = self.wave_manager.current_wave;
             }
         }
@@ -249,6 +396,9 @@ impl GameState {
                     if prev_acc.floor() < self.towers[i].gold_accumulator.floor() {
                         let gold_earned = gold_per_second as u32;
                         self.economy.gold += gold_earned;
+                        self.economy.gold_earned += gold_earned;
+                        self.stats
+                            .record_gold(ProjectileSource::Tower(self.towers[i].id), gold_earned);
                         // Spawn gold pulse effect
                         new_pulses.push(GoldPulse {
                             position: self.towers[i].position.clone(),
@@ -263,6 +413,16 @@ impl GameState {
         }
         self.gold_pulses.extend(new_pulses);
 
+        // 2c. Blueprint auto-build and auto-upgrade policies. Automatic
+        // purchases stay off the undo stack: undoing one would only get it
+        // bought again on the next tick.
+        let recorded = self.history.len();
+        self.auto_build_blueprint();
+        if self.phase == GamePhase::Preparing {
+            self.apply_upgrade_policies();
+        }
+        self.history.truncate(recorded);
+
         // 3. Enemy movement (blocked by shield)
         let center = Point2D::zero();
         let shield_stop = if self.shield.active {
@@ -271,10 +431,47 @@ impl GameState {
             None
         };
         for enemy in &mut self.enemies {
-            enemy.tick(dt, &center, shield_stop);
+            if let Some((source, element, outcome)) = enemy.tick(dt, &center, shield_stop) {
+                self.stats
+                    .record_hit(source, element, ActionKind::Burn, &outcome);
+            }
+        }
+        steering::separate(&mut self.enemies, &center, shield_stop, dt);
+
+        // 3b. Enemy abilities: healing auras and summons
+        let auras: Vec<(usize, Point2D, f32, f32)> = self
+            .enemies
+            .iter()
+            .filter_map(|e| {
+                e.heal_aura()
+                    .map(|(radius, hps)| (e.id, e.position.clone(), radius, hps))
+            })
+            .collect();
+        for enemy in &mut self.enemies {
+            for (healer_id, pos, radius, hps) in &auras {
+                if enemy.id != *healer_id && enemy.position.distance_to(pos) < *radius {
+                    enemy.heal(hps * dt);
+                }
+            }
+        }
+        let mut spawns: Vec<(String, u32, Point2D, f32)> = Vec::new();
+        for enemy in &mut self.enemies {
+            for (preset_id, count) in enemy.tick_summons(dt) {
+                spawns.push((
+                    preset_id,
+                    count,
+                    enemy.position.clone(),
+                    enemy.radius + 20.0,
+                ));
+            }
         }
+        self.spawn_around(spawns);
+
+        // 3c. Boss phases
+        self.tick_bosses(dt);
 
-        // 3. Enemy attacks - enemies target shield if active, otherwise player
+        // 3. Enemy attacks - tower hunters shoot the nearest tower in range,
+        // others target shield if active, otherwise player
         let player_pos = self.player.position.clone();
         let attack_target_radius = if self.shield.active {
             self.shield.radius
@@ -282,7 +479,23 @@ impl GameState {
             0.0
         };
         for enemy in &mut self.enemies {
-            if let Some(proj) = enemy.try_attack(&player_pos, attack_target_radius, dt) {
+            let tower_target = if enemy.targets_towers {
+                find_nearest_tower_in_range(&enemy.position, enemy.attack_range, &self.towers)
+            } else {
+                None
+            };
+            let proj = match tower_target {
+                Some(tower) => {
+                    enemy
+                        .try_attack(&tower.position, tower.radius, dt)
+                        .map(|mut proj| {
+                            proj.target_tower_id = Some(tower.id);
+                            proj
+                        })
+                }
+                None => enemy.try_attack(&player_pos, attack_target_radius, dt),
+            };
+            if let Some(proj) = proj {
                 self.projectiles.push(proj);
             }
         }
@@ -308,9 +521,13 @@ impl GameState {
                     actions: vec![ResolvedAction::ApplyDamage {
                         target: EffectTarget::Single,
                         damage: ResolvedDamage::Fixed(self.player.attack_damage),
+                        armor_pierce: 0.0,
                     }],
                     lifetime: 3.0,
                     target_enemy_id: Some(target_id),
+                    action_kind: None,
+                    target_tower_id: None,
+                    shooter: None,
                     fade_timer: None,
                     size: 4.0,
                 });
@@ -325,6 +542,8 @@ impl GameState {
                 let tower_range = self.towers[i].attack_range();
                 if let Some(_) = find_nearest_in_range(&tower_pos, tower_range, &self.enemies) {
                     let tower = &self.towers[i];
+                    let tower_id = tower.id;
+                    let targeting = tower.targeting;
                     let resolved = tower.resolved_actions();
                     let element = tower.element;
                     let proj_size = get_def(tower.kind).projectile_size;
@@ -346,10 +565,11 @@ impl GameState {
                         .unwrap_or(1);
 
                     // Find targets in range
-                    let target_indices = find_n_nearest_in_range(
+                    let target_indices = find_n_targets_in_range(
                         &tower_pos,
                         tower_range,
                         max_targets,
+                        targeting,
                         &self.enemies,
                     );
 
@@ -362,10 +582,13 @@ impl GameState {
                             current_pos: tower_pos.clone(),
                             speed: 350.0,
                             element,
-                            source: ProjectileSource::Tower(i),
+                            source: ProjectileSource::Tower(tower_id),
                             actions: resolved.clone(),
                             lifetime: 3.0,
                             target_enemy_id: Some(target_id),
+                            action_kind: None,
+                            target_tower_id: None,
+                            shooter: None,
                             fade_timer: None,
                             size: proj_size,
                         });
@@ -376,7 +599,8 @@ impl GameState {
 
         // 6. Projectile movement + collision
         let mut player_damage: f32 = 0.0;
-        let mut enemy_hits: Vec<(usize, Vec<ResolvedAction>, TowerElement, Point2D)> = Vec::new();
+        let mut tower_hits: Vec<(usize, f32)> = Vec::new();
+        let mut enemy_hits: Vec<EnemyHit> = Vec::new();
 
         for proj in &mut self.projectiles {
             // Tick fade-out for dying projectiles
@@ -414,6 +638,8 @@ impl GameState {
                                 proj.actions.clone(),
                                 proj.element,
                                 proj.current_pos.clone(),
+                                proj.source,
+                                proj.action_kind,
                             ));
                             hit = true;
                             break;
@@ -435,7 +661,19 @@ impl GameState {
                         })
                         .sum::<f32>();
 
-                    if self.shield.active {
+                    if let Some(tower_id) = proj.target_tower_id {
+                        let reached =
+                            self.towers
+                                .iter()
+                                .find(|t| t.id == tower_id)
+                                .is_some_and(|t| {
+                                    proj.current_pos.distance_to(&t.position) < t.radius + 5.0
+                                });
+                        if reached {
+                            tower_hits.push((tower_id, damage));
+                            hit = true;
+                        }
+                    } else if self.shield.active {
                         let dist_to_center = proj.current_pos.distance_to(player_pos);
                         if dist_to_center < self.shield.radius + 5.0 {
                             self.shield.hp -= damage;
@@ -477,6 +715,9 @@ impl GameState {
                         }
                     } else if proj.current_pos.distance_to(player_pos) < self.player.radius + 5.0 {
                         player_damage += damage;
+                        if proj.shooter.is_some() {
+                            self.last_hit_by = proj.shooter.clone();
+                        }
                         hit = true;
                     }
                 }
@@ -492,14 +733,22 @@ impl GameState {
         // 7. Apply actions from hits
         self.player.hp -= player_damage;
 
-        for (idx, actions, element, pos) in enemy_hits {
+        for (tower_id, damage) in tower_hits {
+            self.damage_tower(tower_id, damage);
+        }
+
+        for (idx, actions, element, pos, source, action_kind) in enemy_hits {
             if idx >= self.enemies.len() {
                 continue;
             }
 
             for action in &actions {
                 match action {
-                    ResolvedAction::ApplyDamage { target, damage } => {
+                    ResolvedAction::ApplyDamage {
+                        target,
+                        damage,
+                        armor_pierce,
+                    } => {
                         let dmg = match damage {
                             ResolvedDamage::Fixed(d) => *d,
                             ResolvedDamage::PercentHp(pct) => {
@@ -507,12 +756,28 @@ impl GameState {
                             }
                         };
 
+                        let action_kind =
+                            action_kind.unwrap_or_else(|| ActionKind::from_target(target));
                         match target {
                             EffectTarget::Single | EffectTarget::Multi(_) => {
-                                self.enemies[idx].take_damage(dmg, element);
+                                self.damage_enemy(
+                                    idx,
+                                    dmg,
+                                    *armor_pierce,
+                                    element,
+                                    source,
+                                    action_kind,
+                                );
                             }
                             EffectTarget::Chain { count, range } => {
-                                self.enemies[idx].take_damage(dmg, element);
+                                self.damage_enemy(
+                                    idx,
+                                    dmg,
+                                    *armor_pierce,
+                                    element,
+                                    source,
+                                    action_kind,
+                                );
                                 let mut current_pos = self.enemies[idx].position.clone();
                                 let mut hit_indices = vec![idx];
                                 for _ in 0..*count {
@@ -537,13 +802,17 @@ impl GameState {
                                             current_pos: current_pos.clone(),
                                             speed: 250.0,
                                             element,
-                                            source: ProjectileSource::Player,
+                                            source,
                                             actions: vec![ResolvedAction::ApplyDamage {
                                                 target: EffectTarget::Single,
                                                 damage: damage.clone(),
+                                                armor_pierce: *armor_pierce,
                                             }],
                                             lifetime: 1.0,
                                             target_enemy_id: Some(self.enemies[next_idx].id),
+                                            action_kind: Some(ActionKind::Chain),
+                                            target_tower_id: None,
+                                            shooter: None,
                                             fade_timer: None,
                                             size: 3.0,
                                         });
@@ -555,7 +824,14 @@ impl GameState {
                                 }
                             }
                             EffectTarget::Area(radius) => {
-                                self.enemies[idx].take_damage(dmg, element);
+                                self.damage_enemy(
+                                    idx,
+                                    dmg,
+                                    *armor_pierce,
+                                    element,
+                                    source,
+                                    action_kind,
+                                );
                                 let color = element.color();
                                 self.aoe_splashes.push(AoeSplash {
                                     position: pos.clone(),
@@ -564,9 +840,16 @@ impl GameState {
                                     lifetime: 0.4,
                                     max_lifetime: 0.4,
                                 });
-                                for enemy in &mut self.enemies {
-                                    if enemy.position.distance_to(&pos) < *radius {
-                                        enemy.take_damage(dmg * 0.5, element);
+                                for i in 0..self.enemies.len() {
+                                    if self.enemies[i].position.distance_to(&pos) < *radius {
+                                        self.damage_enemy(
+                                            i,
+                                            dmg * 0.5,
+                                            *armor_pierce,
+                                            element,
+                                            source,
+                                            action_kind,
+                                        );
                                     }
                                 }
                             }
@@ -574,10 +857,10 @@ impl GameState {
                     }
                     ResolvedAction::ApplyEffect { target, effect } => match target {
                         EffectTarget::Single | EffectTarget::Multi(_) => {
-                            apply_effect_to_enemy(&mut self.enemies[idx], effect);
+                            apply_effect_to_enemy(&mut self.enemies[idx], effect, source, element);
                         }
                         EffectTarget::Chain { count, range } => {
-                            apply_effect_to_enemy(&mut self.enemies[idx], effect);
+                            apply_effect_to_enemy(&mut self.enemies[idx], effect, source, element);
                             let mut current_pos = self.enemies[idx].position.clone();
                             let mut hit_indices = vec![idx];
                             for _ in 0..*count {
@@ -601,13 +884,16 @@ impl GameState {
                                         current_pos: current_pos.clone(),
                                         speed: 250.0,
                                         element,
-                                        source: ProjectileSource::Player,
+                                        source,
                                         actions: vec![ResolvedAction::ApplyEffect {
                                             target: EffectTarget::Single,
                                             effect: effect.clone(),
                                         }],
                                         lifetime: 1.0,
                                         target_enemy_id: Some(self.enemies[next_idx].id),
+                                        action_kind: Some(ActionKind::Chain),
+                                        target_tower_id: None,
+                                        shooter: None,
                                         fade_timer: None,
                                         size: 3.0,
                                     });
@@ -621,7 +907,7 @@ impl GameState {
                         EffectTarget::Area(radius) => {
                             for enemy in &mut self.enemies {
                                 if enemy.position.distance_to(&pos) < *radius {
-                                    apply_effect_to_enemy(enemy, effect);
+                                    apply_effect_to_enemy(enemy, effect, source, element);
                                 }
                             }
                         }
@@ -632,11 +918,44 @@ impl GameState {
             }
         }
 
-        // 8. Remove dead enemies + award gold + random pepite drops
+        for (tower_id, damage) in std::mem::take(&mut self.reflected_hits) {
+            self.damage_tower(tower_id, damage);
+        }
+
+        // 8. Remove dead enemies + award gold + random pepite drops, splitters
+        // leave their children behind
+        let splits: Vec<(String, u32, Point2D, f32)> = self
+            .enemies
+            .iter()
+            .filter(|e| e.is_dead())
+            .filter_map(|e| {
+                e.split_into()
+                    .map(|(preset_id, count)| (preset_id, count, e.position.clone(), e.radius))
+            })
+            .collect();
+        let explosions: Vec<Point2D> = self
+            .enemies
+            .iter()
+            .filter(|e| e.is_dead() && e.has_affix(EliteAffix::Explosive))
+            .map(|e| e.position.clone())
+            .collect();
+        let boss_kills: Vec<Option<ProjectileSource>> = self
+            .enemies
+            .iter()
+            .filter(|e| e.is_dead() && e.is_boss)
+            .map(|e| e.killed_by)
+            .collect();
         self.enemies.retain(|e| {
             if e.is_dead() {
-                self.economy.gold += e.gold_value;
+                let gold = self.modifiers.enemy_gold(e.gold_value);
+                self.economy.gold += gold;
+                self.economy.gold_earned += gold;
+                // Elites drop one extra pepite per affix
+                self.economy.pepites += e.affixes.len() as u32;
                 self.economy.score += e.gold_value;
+                if let Some(source) = e.killed_by {
+                    self.stats.record_gold(source, gold);
+                }
                 // Pepite drops: bosses always drop 3-5 + bonus tower slot, others 10% chance for 1
                 if e.is_boss {
                     use rand::Rng;
@@ -656,6 +975,17 @@ impl GameState {
             }
         });
 
+        self.spawn_around(splits);
+        for position in explosions {
+            self.explode(position);
+        }
+        for killed_by in boss_kills {
+            self.unlock_achievement(Achievement::FirstBoss);
+            if killed_by == Some(ProjectileSource::Player) {
+                self.unlock_achievement(Achievement::CoupDeGrace);
+            }
+        }
+
         // 9. Remove expired projectiles and tick AoE splashes
         self.projectiles.retain(|p| {
             if let Some(fade) = p.fade_timer {
@@ -677,6 +1007,21 @@ impl GameState {
         }
         self.gold_pulses.retain(|p| p.lifetime > 0.0);
 
+        for number in &mut self.damage_numbers {
+            number.lifetime -= dt;
+        }
+        self.damage_numbers.retain(|n| n.lifetime > 0.0);
+
+        for number in &mut self.gold_numbers {
+            number.lifetime -= dt;
+        }
+        self.gold_numbers.retain(|n| n.lifetime > 0.0);
+
+        for event in &mut self.events {
+            event.lifetime -= dt;
+        }
+        self.events.retain(|e| e.lifetime > 0.0);
+
         // 10. Remove enemies that reached the player (no shield)
         let player_radius = self.player.radius;
         if !self.shield.active {
@@ -684,6 +1029,7 @@ impl GameState {
                 let dist = e.position.distance_to(&center);
                 if dist < player_radius + e.radius {
                     self.player.hp -= e.damage;
+                    self.last_hit_by = Some(e.display_name().to_string());
                     false
                 } else {
                     true
@@ -691,32 +1037,65 @@ impl GameState {
             });
         }
 
+        // 10b. Achievements reached during this tick
+        self.check_achievements();
+
         // 11. Game over check
         if self.player.hp <= 0.0 {
             self.player.hp = 0.0;
             self.phase = GamePhase::GameOver;
-            // Transfer pepites to persistent save
-            self.save_data.pepites += self.economy.pepites;
-            if self.economy.score > self.save_data.best_score {
-                self.save_data.best_score = self.economy.score;
+            if self.settings.test_wave.is_some() {
+                return;
             }
-            if self.economy.wave_number > self.save_data.max_wave {
-                self.save_data.max_wave = self.economy.wave_number;
+            // Transfer pepites to persistent save, scaled by difficulty
+            let pepites = (self.economy.pepites as f32
+                * self.settings.difficulty.pepite_multiplier())
+            .round() as u32;
+            self.save_data.pepites += pepites;
+            if let Some(challenge) = &self.daily {
+                // Daily runs are ranked apart from regular ones, first attempt only
+                if self.daily_ranked
+                    && let Some(result) = self
+                        .save_data
+                        .daily_results
+                        .iter_mut()
+                        .find(|r| r.day == challenge.day)
+                {
+                    result.score = self.economy.score;
+                    result.wave = self.economy.wave_number;
+                    result.finished = true;
+                }
+            } else {
+                if self.economy.score > self.save_data.best_score {
+                    self.save_data.best_score = self.economy.score;
+                    self.save_data.best_score_settings = Some(self.settings.clone());
+                }
+                if self.economy.wave_number > self.save_data.max_wave {
+                    self.save_data.max_wave = self.economy.wave_number;
+                }
             }
             self.save_data.save();
+            self.record_run(pepites);
         }
     }
 
-    pub fn try_place_tower(&mut self, kind: TowerKind, x: f32, y: f32) {
+    /// Daily challenges restrict the buildable towers
+    pub fn is_tower_available(&self, kind: TowerKind) -> bool {
+        self.daily
+            .as_ref()
+            .is_none_or(|challenge| challenge.towers.contains(&kind))
+    }
+
+    pub fn try_place_tower(&mut self, kind: TowerKind, x: f32, y: f32) -> bool {
         use crate::data::tower_defs::get_def;
 
-        if self.towers.len() >= self.max_towers as usize {
-            return;
+        if self.towers.len() >= self.max_towers as usize || !self.is_tower_available(kind) {
+            return false;
         }
 
         let def = get_def(kind);
         if self.economy.gold < def.base_cost {
-            return;
+            return false;
         }
 
         let pos = Point2D::new(x, y);
@@ -724,44 +1103,202 @@ impl GameState {
         // Check not too close to another tower
         for tower in &self.towers {
             if tower.position.distance_to(&pos) < 30.0 {
-                return;
+                return false;
             }
         }
 
         // Check not on top of player
         if pos.distance_to(&Point2D::zero()) < self.player.radius + 20.0 {
-            return;
+            return false;
         }
 
         self.economy.gold -= def.base_cost;
-        let id = self.towers.len();
-        self.towers.push(Tower::from_def(id, kind, pos));
+        let only_sentinelles = self.only_sentinelles;
+        if kind != TowerKind::Sentinelle {
+            self.only_sentinelles = false;
+        }
+        *self.built_count_mut(kind) += 1;
+        let id = self.next_tower_id;
+        self.next_tower_id += 1;
+        let tower = Tower::from_def(id, kind, pos);
+        self.stats.register_tower(&tower);
+        self.towers.push(tower);
+        self.record_action(EconomyAction::Place {
+            tower_id: id,
+            cost: def.base_cost,
+            only_sentinelles,
+        });
+        true
     }
 
-    pub fn try_select_at(&mut self, x: f32, y: f32) {
+    /// Selects the tower under the click; `additive` (shift) toggles it in the selection
+    pub fn try_select_at(&mut self, x: f32, y: f32, additive: bool) {
         let click_pos = Point2D::new(x, y);
 
         // Check if clicked on a tower
-        for (i, tower) in self.towers.iter().enumerate() {
+        for tower in &self.towers {
             if tower.position.distance_to(&click_pos) < tower.radius + 10.0 {
-                if self.selected_tower == Some(i) {
+                if additive {
+                    self.selection.toggle(tower.id);
+                } else if self.selection.ids() == [tower.id] {
                     // Re-click on same tower -> deselect
-                    self.selected_tower = None;
+                    self.selection.clear();
                 } else {
                     // Select new tower (or switch selection)
-                    self.selected_tower = Some(i);
+                    self.selection.set(tower.id);
                 }
                 return;
             }
         }
 
-        self.selected_tower = None;
+        // Clicking a blueprint ghost buys it
+        if let Some(ghost_idx) = self.blueprint_ghost_at(&click_pos) {
+            self.buy_blueprint_ghost(ghost_idx);
+            return;
+        }
+
+        if !additive {
+            self.selection.clear();
+        }
+    }
+
+    /// Box selection between two corners in game coordinates
+    pub fn select_in_box(&mut self, a: &Point2D, b: &Point2D, additive: bool) {
+        if !additive {
+            self.selection.clear();
+        }
+        let (min_x, max_x) = (a.x.min(b.x), a.x.max(b.x));
+        let (min_y, max_y) = (a.y.min(b.y), a.y.max(b.y));
+        for tower in &self.towers {
+            let p = &tower.position;
+            if p.x >= min_x && p.x <= max_x && p.y >= min_y && p.y <= max_y {
+                self.selection.add(tower.id);
+            }
+        }
+    }
+
+    /// Indices of the selected towers that still exist
+    pub fn selected_indices(&self) -> Vec<usize> {
+        self.towers
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| self.selection.contains(t.id))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Index of the selected tower when exactly one is selected
+    pub fn single_selected(&self) -> Option<usize> {
+        match self.selected_indices().as_slice() {
+            [idx] => Some(*idx),
+            _ => None,
+        }
+    }
+
+    /// Upgrades (by name) that every selected tower has
+    pub fn common_selection_upgrades(&self) -> Vec<SelectionUpgrade> {
+        let indices = self.selected_indices();
+        let Some(first) = indices.first() else {
+            return Vec::new();
+        };
+
+        let mut common = Vec::new();
+        for (id, name, _) in self.towers[*first].get_upgrades() {
+            let mut upgradable = 0;
+            let mut total_cost = 0;
+            let mut shared = true;
+            for idx in &indices {
+                let upgrades = self.towers[*idx].get_upgrades();
+                match upgrades.iter().find(|(uid, n, _)| *uid == id && *n == name) {
+                    Some((_, _, prop)) => {
+                        if prop.can_upgrade() {
+                            upgradable += 1;
+                            total_cost += prop.cost();
+                        }
+                    }
+                    None => {
+                        shared = false;
+                        break;
+                    }
+                }
+            }
+            if shared {
+                common.push(SelectionUpgrade {
+                    id,
+                    name,
+                    upgradable,
+                    total_cost,
+                });
+            }
+        }
+        common
+    }
+
+    /// Buys one level of the given upgrade on each selected tower, cheapest
+    /// first, without spending more than `budget`. Returns the number bought.
+    pub fn upgrade_selection(
+        &mut self,
+        id: tower::TowerUpgradeId,
+        name: &str,
+        budget: u32,
+    ) -> usize {
+        let mut purchases: Vec<(usize, tower::TowerUpgradeId, u32)> = self
+            .selected_indices()
+            .into_iter()
+            .filter_map(|idx| {
+                self.towers[idx]
+                    .get_upgrades()
+                    .into_iter()
+                    .find(|(uid, n, prop)| *uid == id && *n == name && prop.can_upgrade())
+                    .map(|(uid, _, prop)| (idx, uid, prop.cost()))
+            })
+            .collect();
+        purchases.sort_by_key(|(_, _, cost)| *cost);
+
+        let mut remaining = budget;
+        let mut bought = 0;
+        for (idx, uid, cost) in purchases {
+            if cost > remaining {
+                break;
+            }
+            if self.upgrade_tower(idx, uid) {
+                remaining -= cost;
+                bought += 1;
+            }
+        }
+        bought
     }
 
+    pub fn sell_selection(&mut self) {
+        // Highest index first so earlier indices stay valid
+        for idx in self.selected_indices().into_iter().rev() {
+            self.sell_tower(idx);
+        }
+        self.selection.clear();
+    }
+
+    pub fn set_selection_targeting(&mut self, mode: TargetingMode) {
+        for tower in &mut self.towers {
+            if self.selection.contains(tower.id) {
+                tower.targeting = mode;
+            }
+        }
+    }
+
+    /// Calls the next wave before the preparation timer runs out, paying a
+    /// bonus for the time skipped
     pub fn start_wave(&mut self) {
         if self.phase == GamePhase::Preparing {
+            let bonus = self.wave_manager.early_call_bonus();
+            if bonus > 0 {
+                self.economy.gold += bonus;
+                self.economy.gold_earned += bonus;
+                self.events
+                    .push(GameEvent::new(GameEventKind::EarlyCall { gold: bonus }));
+            }
             self.wave_manager.start_next_wave();
             self.phase = GamePhase::Active;
+            self.history.clear();
             self.economy.wave_number = self.wave_manager.current_wave;
         }
     }
@@ -786,24 +1323,117 @@ impl GameState {
             return false;
         }
         self.economy.gold -= cost;
-        self.towers[tower_idx].apply_upgrade(upgrade_id)
+        if !self.towers[tower_idx].apply_upgrade(upgrade_id) {
+            return false;
+        }
+        self.towers[tower_idx].invested += cost;
+        self.record_action(EconomyAction::Upgrade {
+            tower_id: self.towers[tower_idx].id,
+            upgrade_id,
+            cost,
+        });
+        true
     }
 
     pub fn sell_tower(&mut self, tower_idx: usize) {
         if tower_idx >= self.towers.len() {
             return;
         }
-        let value = self.towers[tower_idx].sell_value();
+        let value = self.sell_value(tower_idx);
         self.economy.gold += value;
-        self.towers.remove(tower_idx);
-        self.selected_tower = None;
+        let tower = self.towers.remove(tower_idx);
+        self.selection.clear();
+        self.record_action(EconomyAction::Sell {
+            tower,
+            index: tower_idx,
+            refund: value,
+        });
+    }
+
+    /// Restores a tower to full HP for `Tower::repair_cost` gold
+    pub fn repair_tower(&mut self, tower_idx: usize) -> bool {
+        let Some(tower) = self.towers.get_mut(tower_idx) else {
+            return false;
+        };
+        let cost = tower.repair_cost();
+        if cost == 0 || self.economy.gold < cost {
+            return false;
+        }
+        self.economy.gold -= cost;
+        tower.hp = tower.max_hp;
+        true
+    }
+
+    fn damage_tower(&mut self, tower_id: usize, damage: f32) {
+        if let Some(idx) = self.towers.iter().position(|t| t.id == tower_id) {
+            self.towers[idx].hp -= damage;
+            if self.towers[idx].hp <= 0.0 {
+                self.destroy_tower(idx);
+            }
+        }
+    }
+
+    /// Death blast of an explosive elite, hurting every tower in range
+    fn explode(&mut self, position: Point2D) {
+        self.aoe_splashes.push(AoeSplash {
+            position: position.clone(),
+            radius: EXPLOSION_RADIUS,
+            color: (0.06, 1.0, 0.5),
+            lifetime: 0.5,
+            max_lifetime: 0.5,
+        });
+        let hit: Vec<usize> = self
+            .towers
+            .iter()
+            .filter(|t| t.position.distance_to(&position) <= EXPLOSION_RADIUS + t.radius)
+            .map(|t| t.id)
+            .collect();
+        for tower_id in hit {
+            self.damage_tower(tower_id, EXPLOSION_DAMAGE);
+        }
+    }
+
+    /// Removes a tower brought down by enemies, without refund. A blueprint
+    /// ghost it filled becomes buildable again.
+    fn destroy_tower(&mut self, tower_idx: usize) {
+        let tower = self.towers.remove(tower_idx);
+        if self.selection.contains(tower.id) {
+            self.selection.toggle(tower.id);
+        }
+        self.move_mode = None;
+
+        let color = tower.element.color();
+        self.aoe_splashes.push(AoeSplash {
+            position: tower.position.clone(),
+            radius: tower.radius * 3.0,
+            color: (color.h, color.s, color.l),
+            lifetime: 0.6,
+            max_lifetime: 0.6,
+        });
+        let event = GameEvent::new(GameEventKind::TowerDestroyed { name: tower.name });
+
+        let notify = self.towers.iter().any(|t| {
+            t.notification_settings
+                .as_ref()
+                .is_some_and(|s| s.tower_destroyed)
+        });
+        if notify {
+            send_notification("Sentinels", &event.message());
+        }
+        self.events.push(event);
+    }
+
+    pub fn sell_value(&self, tower_idx: usize) -> u32 {
+        self.towers
+            .get(tower_idx)
+            .map_or(0, |t| t.sell_value(self.refund_ratio))
     }
 
     pub fn move_cost(&self, tower_idx: usize) -> u32 {
         if tower_idx >= self.towers.len() {
             return 0;
         }
-        (self.towers[tower_idx].sell_value() + 3) / 4
+        (self.sell_value(tower_idx) + 3) / 4
     }
 
     pub fn try_move_tower(&mut self, tower_idx: usize, x: f32, y: f32) -> bool {
@@ -836,17 +1466,443 @@ impl GameState {
         }
 
         self.economy.gold -= cost;
-        self.towers[tower_idx].position = new_pos;
+        let from = std::mem::replace(&mut self.towers[tower_idx].position, new_pos);
         self.move_mode = None;
-        self.selected_tower = None;
+        self.selection.clear();
+        self.record_action(EconomyAction::Move {
+            tower_id: self.towers[tower_idx].id,
+            from,
+            cost,
+        });
         true
     }
+
+    fn record_action(&mut self, action: EconomyAction) {
+        if self.phase == GamePhase::Preparing {
+            self.history.push(action);
+        }
+    }
+
+    /// Reverts the last economy action of this preparation phase with a full refund
+    pub fn undo_last_action(&mut self) -> bool {
+        if self.phase != GamePhase::Preparing {
+            return false;
+        }
+        // A sold tower only comes back once its refund can be paid back
+        if let Some(EconomyAction::Sell { refund, .. }) = self.history.last()
+            && self.economy.gold < *refund
+        {
+            return false;
+        }
+        let Some(action) = self.history.pop() else {
+            return false;
+        };
+        self.selection.clear();
+        self.move_mode = None;
+
+        match action {
+            EconomyAction::Place {
+                tower_id,
+                cost,
+                only_sentinelles,
+            } => {
+                if let Some(kind) = self
+                    .towers
+                    .iter()
+                    .find(|t| t.id == tower_id)
+                    .map(|t| t.kind)
+                {
+                    let built = self.built_count_mut(kind);
+                    *built = built.saturating_sub(1);
+                }
+                // Upgrades bought automatically since the placement go back too
+                let refund = self
+                    .towers
+                    .iter()
+                    .find(|t| t.id == tower_id)
+                    .map_or(cost, |t| t.invested);
+                self.towers.retain(|t| t.id != tower_id);
+                self.stats.unregister_tower(tower_id);
+                self.economy.gold += refund;
+                self.only_sentinelles = only_sentinelles;
+            }
+            EconomyAction::Upgrade {
+                tower_id,
+                upgrade_id,
+                cost,
+            } => {
+                let reverted = self
+                    .towers
+                    .iter_mut()
+                    .find(|t| t.id == tower_id)
+                    .is_some_and(|t| {
+                        let reverted = t.revert_upgrade(upgrade_id);
+                        if reverted {
+                            t.invested -= cost;
+                        }
+                        reverted
+                    });
+                if reverted {
+                    self.economy.gold += cost;
+                }
+            }
+            EconomyAction::Sell {
+                tower,
+                index,
+                refund,
+            } => {
+                self.economy.gold -= refund;
+                let index = index.min(self.towers.len());
+                self.towers.insert(index, tower);
+            }
+            EconomyAction::Move {
+                tower_id,
+                from,
+                cost,
+            } => {
+                if let Some(tower) = self.towers.iter_mut().find(|t| t.id == tower_id) {
+                    tower.position = from;
+                    self.economy.gold += cost;
+                }
+            }
+        }
+        true
+    }
+
+    pub fn load_blueprint(&mut self, blueprint: &Blueprint) {
+        self.blueprint = Some(ActiveBlueprint::new(blueprint));
+    }
+
+    /// Index of the unbuilt blueprint ghost under `pos`, if any
+    fn blueprint_ghost_at(&self, pos: &Point2D) -> Option<usize> {
+        let blueprint = self.blueprint.as_ref()?;
+        blueprint.ghosts.iter().position(|g| {
+            self.blueprint_tower_idx(g.tower_id).is_none()
+                && Point2D::new(g.plan.x, g.plan.y).distance_to(pos) < 24.0
+        })
+    }
+
+    /// Blueprint towers not built yet, drawn as ghosts on the canvas
+    pub fn unbuilt_blueprint_towers(&self) -> Vec<&BlueprintTower> {
+        self.blueprint
+            .iter()
+            .flat_map(|b| b.ghosts.iter())
+            .filter(|g| self.blueprint_tower_idx(g.tower_id).is_none())
+            .map(|g| &g.plan)
+            .collect()
+    }
+
+    fn blueprint_tower_idx(&self, tower_id: Option<usize>) -> Option<usize> {
+        let id = tower_id?;
+        self.towers.iter().position(|t| t.id == id)
+    }
+
+    /// Next purchase for a ghost: placement first, then upgrades toward the plan
+    pub fn next_blueprint_purchase(&self, ghost_idx: usize) -> Option<BlueprintPurchase> {
+        let ghost = self.blueprint.as_ref()?.ghosts.get(ghost_idx)?;
+        let Some(tower_idx) = self.blueprint_tower_idx(ghost.tower_id) else {
+            return Some(BlueprintPurchase::Place {
+                kind: ghost.plan.kind,
+                x: ghost.plan.x,
+                y: ghost.plan.y,
+            });
+        };
+
+        self.towers[tower_idx]
+            .get_upgrades()
+            .iter()
+            .zip(ghost.plan.upgrade_levels.iter())
+            .find(|((_, _, prop), target)| prop.current_level < **target && prop.can_upgrade())
+            .map(|((upgrade_id, _, _), _)| BlueprintPurchase::Upgrade {
+                tower_idx,
+                upgrade_id: *upgrade_id,
+            })
+    }
+
+    pub fn blueprint_purchase_cost(&self, purchase: BlueprintPurchase) -> u32 {
+        match purchase {
+            BlueprintPurchase::Place { kind, .. } => get_def(kind).base_cost,
+            BlueprintPurchase::Upgrade {
+                tower_idx,
+                upgrade_id,
+            } => self.towers[tower_idx]
+                .upgrade_cost(upgrade_id)
+                .unwrap_or(u32::MAX),
+        }
+    }
+
+    pub fn buy_blueprint_ghost(&mut self, ghost_idx: usize) -> bool {
+        let Some(purchase) = self.next_blueprint_purchase(ghost_idx) else {
+            return false;
+        };
+        match purchase {
+            BlueprintPurchase::Place { kind, x, y } => {
+                if !self.try_place_tower(kind, x, y) {
+                    return false;
+                }
+                let id = self.towers.last().map(|t| t.id);
+                if let Some(blueprint) = &mut self.blueprint {
+                    blueprint.ghosts[ghost_idx].tower_id = id;
+                }
+                true
+            }
+            BlueprintPurchase::Upgrade {
+                tower_idx,
+                upgrade_id,
+            } => self.upgrade_tower(tower_idx, upgrade_id),
+        }
+    }
+
+    /// Buys blueprint steps in order while gold allows; ghosts that can't be
+    /// placed (slot limit, blocked spot) are skipped
+    fn auto_build_blueprint(&mut self) {
+        let ghost_count = match &self.blueprint {
+            Some(blueprint) if blueprint.auto_build => blueprint.ghosts.len(),
+            _ => return,
+        };
+        for ghost_idx in 0..ghost_count {
+            while let Some(purchase) = self.next_blueprint_purchase(ghost_idx) {
+                if self.economy.gold < self.blueprint_purchase_cost(purchase) {
+                    return;
+                }
+                if !self.buy_blueprint_ghost(ghost_idx) {
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Advances boss phases on HP thresholds and runs the active phase:
+    /// volleys at the core, summons, or enrage with a temporary immunity
+    fn tick_bosses(&mut self, dt: f32) {
+        let core = self.player.position.clone();
+        let mut spawns: Vec<(String, u32, Point2D, f32)> = Vec::new();
+        for enemy in &mut self.enemies {
+            let hp_ratio = enemy.hp / enemy.max_hp;
+            let Some(boss) = &mut enemy.boss else {
+                continue;
+            };
+            if let Some(phase) = boss.advance_phase(hp_ratio) {
+                self.events.push(GameEvent::new(GameEventKind::BossPhase {
+                    boss: boss.def.name,
+                    phase: phase.name,
+                }));
+                if let BossPhaseKind::Enrage {
+                    speed_multiplier,
+                    immune,
+                    duration,
+                } = phase.kind
+                {
+                    enemy.speed *= speed_multiplier;
+                    boss.immunity = Some((immune, duration));
+                }
+            }
+            boss.tick_immunity(dt);
+
+            if enemy.stun_duration > 0.0 {
+                continue;
+            }
+            match boss.active_phase().map(|p| p.kind) {
+                Some(BossPhaseKind::Volley { count, interval })
+                    if boss.tick_phase_timer(dt, interval) =>
+                {
+                    for i in 0..count {
+                        // Fan the shots out from around the boss
+                        let angle = std::f32::consts::TAU * i as f32 / count as f32;
+                        let origin = Point2D::new(
+                            enemy.position.x + enemy.radius * angle.cos(),
+                            enemy.position.y + enemy.radius * angle.sin(),
+                        );
+                        self.projectiles.push(Projectile {
+                            origin: origin.clone(),
+                            target_pos: core.clone(),
+                            current_pos: origin,
+                            speed: 160.0,
+                            element: TowerElement::Neutral,
+                            source: ProjectileSource::Enemy(enemy.id),
+                            actions: vec![ResolvedAction::ApplyDamage {
+                                target: EffectTarget::Single,
+                                damage: ResolvedDamage::Fixed(enemy.damage * 0.3),
+                                armor_pierce: 0.0,
+                            }],
+                            lifetime: 5.0,
+                            target_enemy_id: None,
+                            action_kind: None,
+                            target_tower_id: None,
+                            shooter: Some(enemy.display_name().to_string()),
+                            fade_timer: None,
+                            size: 4.0,
+                        });
+                    }
+                }
+                Some(BossPhaseKind::Summon {
+                    enemy: summoned,
+                    count,
+                    interval,
+                }) if boss.tick_phase_timer(dt, interval) => {
+                    spawns.push((
+                        summoned.to_string(),
+                        count,
+                        enemy.position.clone(),
+                        enemy.radius + 25.0,
+                    ));
+                }
+                _ => {}
+            }
+        }
+        self.spawn_around(spawns);
+    }
+
+    /// Spawns `count` enemies of a preset evenly spread on a circle around each position
+    fn spawn_around(&mut self, spawns: Vec<(String, u32, Point2D, f32)>) {
+        for (preset_id, count, pos, radius) in spawns {
+            for i in 0..count {
+                let angle = std::f32::consts::TAU * i as f32 / count as f32;
+                let spawn_pos =
+                    Point2D::new(pos.x + radius * angle.cos(), pos.y + radius * angle.sin());
+                if let Some(enemy) = self.wave_manager.spawn_minion(&preset_id, spawn_pos) {
+                    self.enemies.push(enemy);
+                }
+            }
+        }
+    }
+
+    /// Spends gold on tower upgrade policies, one level per tower per pass so
+    /// towers share the budget
+    fn apply_upgrade_policies(&mut self) {
+        loop {
+            let mut bought = false;
+            for idx in 0..self.towers.len() {
+                let Some((upgrade_id, cost, reserve)) = self.towers[idx].next_policy_upgrade()
+                else {
+                    continue;
+                };
+                if self.economy.gold >= cost + reserve && self.upgrade_tower(idx, upgrade_id) {
+                    bought = true;
+                }
+            }
+            if !bought {
+                break;
+            }
+        }
+    }
+
+    fn built_count_mut(&mut self, kind: TowerKind) -> &mut u32 {
+        let pos = match self.towers_built.iter().position(|(k, _)| *k == kind) {
+            Some(pos) => pos,
+            None => {
+                self.towers_built.push((kind, 0));
+                self.towers_built.len() - 1
+            }
+        };
+        &mut self.towers_built[pos].1
+    }
+
+    /// Appends the finished run to the history file of the profile
+    fn record_run(&self, pepites: u32) {
+        let Some(dir) = self.save_data.profile_dir() else {
+            return;
+        };
+        RunRecord {
+            ended_at: RunRecord::now(),
+            seed: self.wave_manager.seed,
+            settings: self.settings.clone(),
+            score: self.economy.score,
+            wave: self.economy.wave_number,
+            towers_built: self.towers_built.clone(),
+            gold_earned: self.economy.gold_earned,
+            pepites,
+            cause_of_death: self
+                .last_hit_by
+                .clone()
+                .unwrap_or_else(|| "Inconnue".to_string()),
+            duration: self.elapsed,
+        }
+        .append(dir);
+    }
+
+    /// Waves fully beaten so far
+    fn waves_cleared(&self) -> u32 {
+        match self.phase {
+            GamePhase::Active => self.economy.wave_number.saturating_sub(1),
+            _ => self.economy.wave_number,
+        }
+    }
+
+    fn check_achievements(&mut self) {
+        let wave = self.economy.wave_number;
+        if wave >= NO_SHIELD_WAVE && !self.shield.is_unlocked() {
+            self.unlock_achievement(Achievement::NoShieldWave20);
+        }
+        if wave >= SURVIVOR_WAVE {
+            self.unlock_achievement(Achievement::Wave30);
+        }
+        if self.stats.reaction_count(ElementalReaction::Steam) >= STEAM_REACTIONS_GOAL {
+            self.unlock_achievement(Achievement::SteamMaster);
+        }
+        if self.only_sentinelles
+            && !self.towers.is_empty()
+            && self.waves_cleared() >= OLD_GUARD_WAVE
+        {
+            self.unlock_achievement(Achievement::OldGuard);
+        }
+    }
+
+    /// Saves the achievement right away and toasts it, once per profile.
+    /// Wave editor test runs don't count.
+    fn unlock_achievement(&mut self, achievement: Achievement) {
+        if self.settings.test_wave.is_some() || !self.save_data.unlock_achievement(achievement) {
+            return;
+        }
+        self.events
+            .push(GameEvent::new(GameEventKind::AchievementUnlocked {
+                achievement,
+            }));
+    }
+
+    fn damage_enemy(
+        &mut self,
+        idx: usize,
+        damage: f32,
+        armor_pierce: f32,
+        element: TowerElement,
+        source: ProjectileSource,
+        action: ActionKind,
+    ) {
+        let outcome = self.enemies[idx].take_damage(damage, element, armor_pierce);
+        if outcome.raw > 0.0 {
+            if self.damage_numbers.len() >= MAX_DAMAGE_NUMBERS {
+                self.damage_numbers.remove(0);
+            }
+            self.damage_numbers.push(DamageNumber {
+                position: self.enemies[idx].position.clone(),
+                amount: outcome.raw - outcome.mitigated,
+                raw: outcome.raw,
+                lifetime: DAMAGE_NUMBER_DURATION,
+            });
+        }
+        if outcome.killed {
+            self.enemies[idx].killed_by = Some(source);
+        }
+        if let ProjectileSource::Tower(tower_id) = source
+            && self.enemies[idx].has_affix(EliteAffix::Reflective)
+        {
+            let dealt = outcome.raw - outcome.mitigated;
+            self.reflected_hits.push((tower_id, dealt * REFLECT_SHARE));
+        }
+        self.stats.record_hit(source, element, action, &outcome);
+    }
 }
 
-fn apply_effect_to_enemy(enemy: &mut Enemy, effect: &ResolvedEffect) {
+fn apply_effect_to_enemy(
+    enemy: &mut Enemy,
+    effect: &ResolvedEffect,
+    source: ProjectileSource,
+    element: TowerElement,
+) {
     match effect {
         ResolvedEffect::Burn { dps, duration } => {
-            enemy.apply_burn(*dps, *duration);
+            enemy.apply_burn(*dps, *duration, source, element);
         }
         ResolvedEffect::Slow { ratio, duration } => {
             enemy.apply_slow(*ratio, *duration);
@@ -857,6 +1913,21 @@ fn apply_effect_to_enemy(enemy: &mut Enemy, effect: &ResolvedEffect) {
     }
 }
 
+fn find_nearest_tower_in_range<'a>(
+    pos: &Point2D,
+    range: f32,
+    towers: &'a [Tower],
+) -> Option<&'a Tower> {
+    towers
+        .iter()
+        .filter(|t| pos.distance_to(&t.position) <= range + t.radius)
+        .min_by(|a, b| {
+            pos.distance_to(&a.position)
+                .partial_cmp(&pos.distance_to(&b.position))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        })
+}
+
 fn find_nearest_in_range(pos: &Point2D, range: f32, enemies: &[Enemy]) -> Option<usize> {
     enemies
         .iter()
@@ -870,13 +1941,110 @@ fn find_nearest_in_range(pos: &Point2D, range: f32, enemies: &[Enemy]) -> Option
         .map(|(idx, _)| idx)
 }
 
-fn find_n_nearest_in_range(pos: &Point2D, range: f32, n: usize, enemies: &[Enemy]) -> Vec<usize> {
+fn find_n_targets_in_range(
+    pos: &Point2D,
+    range: f32,
+    n: usize,
+    mode: TargetingMode,
+    enemies: &[Enemy],
+) -> Vec<usize> {
+    let center = Point2D::zero();
+    // Lower key = higher priority
     let mut indexed: Vec<(usize, f32)> = enemies
         .iter()
         .enumerate()
         .filter(|(_, e)| pos.distance_to(&e.position) <= range)
-        .map(|(i, e)| (i, pos.distance_to(&e.position)))
+        .map(|(i, e)| {
+            let key = match mode {
+                TargetingMode::Nearest => pos.distance_to(&e.position),
+                TargetingMode::First => center.distance_to(&e.position),
+                TargetingMode::Strongest => -e.hp,
+                TargetingMode::Weakest => e.hp,
+            };
+            (i, key)
+        })
         .collect();
     indexed.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
     indexed.into_iter().take(n).map(|(i, _)| i).collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_game() -> GameState {
+        let mut game = GameState::new(
+            &SaveData::default(),
+            RunSettings::default(),
+            WaveScript::builtin(),
+        );
+        game.economy.gold = 1000;
+        game
+    }
+
+    #[test]
+    fn auto_built_towers_stay_off_the_undo_stack() {
+        let mut game = test_game();
+        game.load_blueprint(&Blueprint {
+            name: "Test".to_string(),
+            towers: vec![BlueprintTower {
+                kind: TowerKind::Sentinelle,
+                x: 100.0,
+                y: 0.0,
+                upgrade_levels: Vec::new(),
+            }],
+        });
+        if let Some(blueprint) = &mut game.blueprint {
+            blueprint.auto_build = true;
+        }
+
+        game.tick(0.01);
+        assert_eq!(game.towers.len(), 1);
+        assert!(!game.undo_last_action());
+        assert_eq!(game.towers.len(), 1);
+    }
+
+    #[test]
+    fn policy_upgrades_stay_off_the_undo_stack() {
+        let mut game = test_game();
+        assert!(game.try_place_tower(TowerKind::Sentinelle, 100.0, 0.0));
+        let policy = game.towers[0].default_upgrade_policy();
+        game.towers[0].upgrade_policy = Some(policy);
+
+        game.tick(0.01);
+        assert!(game.towers[0].level() > 1);
+
+        // Undo reaches the placement, refunding the upgrades with it
+        assert!(game.undo_last_action());
+        assert!(game.towers.is_empty());
+        assert_eq!(game.economy.gold, 1000);
+    }
+
+    #[test]
+    fn undoing_a_sale_needs_the_refund_back() {
+        let mut game = test_game();
+        assert!(game.try_place_tower(TowerKind::Sentinelle, 100.0, 0.0));
+        game.sell_tower(0);
+        // The refund gets spent, e.g. on another tower
+        game.economy.gold = 0;
+
+        assert!(!game.undo_last_action());
+        assert!(game.towers.is_empty());
+        assert_eq!(game.economy.gold, 0);
+
+        // Still on the stack once the gold is back
+        game.economy.gold = 1000;
+        assert!(game.undo_last_action());
+        assert_eq!(game.towers.len(), 1);
+    }
+
+    #[test]
+    fn undoing_a_placement_restores_the_sentinelles_only_flag() {
+        let mut game = test_game();
+        assert!(game.try_place_tower(TowerKind::Inferno, 100.0, 0.0));
+        assert!(!game.only_sentinelles);
+
+        assert!(game.undo_last_action());
+        assert!(game.only_sentinelles);
+    }
+}
@@ -0,0 +1,85 @@
+use super::player::Player;
+use crate::data::difficulty::{Mutator, RunSettings};
+use crate::data::{SHOP_UPGRADES, SaveData, ShopEffect};
+
+/// Share of banked gold paid as interest at the end of a wave
+const BASE_INTEREST_RATE: f32 = 0.05;
+/// Most interest paid for a single wave
+const BASE_INTEREST_CAP: u32 = 50;
+
+/// Shop upgrades folded into the bonuses of one run, resolved at run start
+#[derive(Clone)]
+pub struct RunModifiers {
+    pub starting_gold: u32,
+    pub damage_multiplier: f32,
+    pub bonus_range: f32,
+    pub bonus_hp: f32,
+    /// Applied to the gold dropped by enemies
+    pub gold_earn_multiplier: f32,
+    pub shield_level: u32,
+    pub bonus_tower_slots: u32,
+    pub interest_rate: f32,
+    pub interest_cap: u32,
+}
+
+impl RunModifiers {
+    pub fn new(save_data: &SaveData, settings: &RunSettings) -> Self {
+        let mut modifiers = Self {
+            starting_gold: settings.difficulty.starting_gold(),
+            damage_multiplier: 1.0,
+            bonus_range: 0.0,
+            bonus_hp: 0.0,
+            gold_earn_multiplier: 1.0,
+            shield_level: 0,
+            bonus_tower_slots: 0,
+            interest_rate: BASE_INTEREST_RATE,
+            interest_cap: BASE_INTEREST_CAP,
+        };
+        // Daily challenges ignore the shop so everyone plays the same run
+        if settings.daily.is_none() {
+            for def in SHOP_UPGRADES {
+                let level = save_data.get_upgrade_level(def.id);
+                if level > 0 {
+                    modifiers.apply(def.effect, level);
+                }
+            }
+        }
+        if settings.has(Mutator::NoShield) {
+            modifiers.shield_level = 0;
+        }
+        modifiers
+    }
+
+    fn apply(&mut self, effect: ShopEffect, level: u32) {
+        let levels = level as f32;
+        match effect {
+            ShopEffect::StartingGold(gold) => self.starting_gold += gold * level,
+            ShopEffect::PlayerDamagePercent(pct) => self.damage_multiplier += pct * levels / 100.0,
+            ShopEffect::PlayerRange(range) => self.bonus_range += range * levels,
+            ShopEffect::PlayerHp(hp) => self.bonus_hp += hp * levels,
+            ShopEffect::GoldEarnPercent(pct) => self.gold_earn_multiplier += pct * levels / 100.0,
+            ShopEffect::ShieldLevel => self.shield_level += level,
+            ShopEffect::TowerSlots(slots) => self.bonus_tower_slots += slots * level,
+            ShopEffect::Interest { percent, cap } => {
+                self.interest_rate += percent * levels / 100.0;
+                self.interest_cap += cap * level;
+            }
+        }
+    }
+
+    pub fn apply_to_player(&self, player: &mut Player) {
+        player.max_hp += self.bonus_hp;
+        player.hp = player.max_hp;
+        player.attack_damage *= self.damage_multiplier;
+        player.attack_range += self.bonus_range;
+    }
+
+    pub fn enemy_gold(&self, base: u32) -> u32 {
+        (base as f32 * self.gold_earn_multiplier).round() as u32
+    }
+
+    /// Interest earned on `banked` gold at the end of a wave
+    pub fn interest(&self, banked: u32) -> u32 {
+        ((banked as f32 * self.interest_rate) as u32).min(self.interest_cap)
+    }
+}
@@ -0,0 +1,54 @@
+use crate::game::tower::TowerUpgradeId;
+
+/// Towers selected on the canvas, stored by tower id so selling or undoing
+/// doesn't shift the selection onto other towers
+#[derive(Clone, Default)]
+pub struct TowerSelection {
+    ids: Vec<usize>,
+}
+
+impl TowerSelection {
+    pub fn ids(&self) -> &[usize] {
+        &self.ids
+    }
+
+    pub fn contains(&self, tower_id: usize) -> bool {
+        self.ids.contains(&tower_id)
+    }
+
+    pub fn clear(&mut self) {
+        self.ids.clear();
+    }
+
+    /// Replaces the selection with a single tower
+    pub fn set(&mut self, tower_id: usize) {
+        self.ids.clear();
+        self.ids.push(tower_id);
+    }
+
+    pub fn toggle(&mut self, tower_id: usize) {
+        if let Some(pos) = self.ids.iter().position(|id| *id == tower_id) {
+            self.ids.remove(pos);
+        } else {
+            self.ids.push(tower_id);
+        }
+    }
+
+    pub fn add(&mut self, tower_id: usize) {
+        if !self.contains(tower_id) {
+            self.ids.push(tower_id);
+        }
+    }
+}
+
+/// An upgrade shared by every selected tower, matched by id and name since
+/// action upgrade ids are positional and differ between tower kinds
+#[derive(Clone)]
+pub struct SelectionUpgrade {
+    pub id: TowerUpgradeId,
+    pub name: &'static str,
+    /// Towers in the selection that can still take this upgrade
+    pub upgradable: usize,
+    /// Gold needed to buy one more level on each of them
+    pub total_cost: u32,
+}
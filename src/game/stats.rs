@@ -0,0 +1,183 @@
+use super::ProjectileSource;
+use super::elemental::{ElementalReaction, TowerElement};
+use super::enemy::DamageOutcome;
+use super::tower::Tower;
+use crate::data::tower_defs::EffectTarget;
+
+/// How a hit was delivered, used to break damage down per action type
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ActionKind {
+    Single,
+    Multi,
+    Area,
+    Chain,
+    Burn,
+}
+
+impl ActionKind {
+    pub fn from_target(target: &EffectTarget) -> Self {
+        match target {
+            EffectTarget::Single => ActionKind::Single,
+            EffectTarget::Multi(_) => ActionKind::Multi,
+            EffectTarget::Area(_) => ActionKind::Area,
+            EffectTarget::Chain { .. } => ActionKind::Chain,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ActionKind::Single => "Cible unique",
+            ActionKind::Multi => "Multi-cibles",
+            ActionKind::Area => "Zone",
+            ActionKind::Chain => "Chaine",
+            ActionKind::Burn => "Brulure",
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct DamageStats {
+    pub damage: f32,
+    pub kills: u32,
+    pub overkill: f32,
+    pub reactions: u32,
+    pub gold: u32,
+}
+
+impl DamageStats {
+    fn add_outcome(&mut self, outcome: &DamageOutcome) {
+        self.damage += outcome.dealt;
+        self.overkill += outcome.overkill;
+        if outcome.killed {
+            self.kills += 1;
+        }
+        if outcome.reaction.is_some() {
+            self.reactions += 1;
+        }
+    }
+}
+
+/// Stats for a single damage source (the player or one tower, even once sold)
+#[derive(Clone)]
+pub struct SourceStats {
+    pub source: ProjectileSource,
+    pub name: String,
+    pub element: TowerElement,
+    pub stats: DamageStats,
+}
+
+#[derive(Clone)]
+pub struct RunStats {
+    pub sources: Vec<SourceStats>,
+    pub elements: Vec<(TowerElement, DamageStats)>,
+    pub actions: Vec<(ActionKind, DamageStats)>,
+    pub reactions: Vec<(ElementalReaction, u32)>,
+}
+
+impl RunStats {
+    pub fn new() -> Self {
+        Self {
+            sources: vec![SourceStats {
+                source: ProjectileSource::Player,
+                name: "Joueur".to_string(),
+                element: TowerElement::Neutral,
+                stats: DamageStats::default(),
+            }],
+            elements: Vec::new(),
+            actions: Vec::new(),
+            reactions: Vec::new(),
+        }
+    }
+
+    pub fn register_tower(&mut self, tower: &Tower) {
+        self.sources.push(SourceStats {
+            source: ProjectileSource::Tower(tower.id),
+            name: format!("{} #{}", tower.name, tower.id + 1),
+            element: tower.element,
+            stats: DamageStats::default(),
+        });
+    }
+
+    /// Drops a tower that never fought, e.g. an undone placement
+    pub fn unregister_tower(&mut self, tower_id: usize) {
+        self.sources
+            .retain(|s| s.source != ProjectileSource::Tower(tower_id));
+    }
+
+    pub fn record_hit(
+        &mut self,
+        source: ProjectileSource,
+        element: TowerElement,
+        action: ActionKind,
+        outcome: &DamageOutcome,
+    ) {
+        if let Some(entry) = self.sources.iter_mut().find(|s| s.source == source) {
+            entry.stats.add_outcome(outcome);
+        } else {
+            // Enemy projectiles are not tracked
+            return;
+        }
+        self.element_mut(element).add_outcome(outcome);
+        self.action_mut(action).add_outcome(outcome);
+        if let Some(reaction) = outcome.reaction {
+            if let Some((_, count)) = self.reactions.iter_mut().find(|(r, _)| *r == reaction) {
+                *count += 1;
+            } else {
+                self.reactions.push((reaction, 1));
+            }
+        }
+    }
+
+    pub fn record_gold(&mut self, source: ProjectileSource, gold: u32) {
+        if let Some(entry) = self.sources.iter_mut().find(|s| s.source == source) {
+            entry.stats.gold += gold;
+            let element = entry.element;
+            self.element_mut(element).gold += gold;
+        }
+    }
+
+    pub fn reaction_count(&self, reaction: ElementalReaction) -> u32 {
+        self.reactions
+            .iter()
+            .find(|(r, _)| *r == reaction)
+            .map_or(0, |(_, count)| *count)
+    }
+
+    pub fn total_damage(&self) -> f32 {
+        self.sources.iter().map(|s| s.stats.damage).sum()
+    }
+
+    /// Sources sorted by damage dealt, highest first
+    pub fn ranked_sources(&self) -> Vec<&SourceStats> {
+        let mut ranked: Vec<&SourceStats> = self.sources.iter().collect();
+        ranked.sort_by(|a, b| {
+            b.stats
+                .damage
+                .partial_cmp(&a.stats.damage)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        ranked
+    }
+
+    fn element_mut(&mut self, element: TowerElement) -> &mut DamageStats {
+        let pos = match self.elements.iter().position(|(e, _)| *e == element) {
+            Some(pos) => pos,
+            None => {
+                self.elements.push((element, DamageStats::default()));
+                self.elements.len() - 1
+            }
+        };
+        &mut self.elements[pos].1
+    }
+
+    fn action_mut(&mut self, action: ActionKind) -> &mut DamageStats {
+        let pos = match self.actions.iter().position(|(a, _)| *a == action) {
+            Some(pos) => pos,
+            None => {
+                self.actions.push((action, DamageStats::default()));
+                self.actions.len() - 1
+            }
+        };
+        &mut self.actions[pos].1
+    }
+}
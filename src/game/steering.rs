@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+
+use super::Point2D;
+use super::enemy::Enemy;
+
+/// Extra spacing kept between enemy bodies
+const SEPARATION_PADDING: f32 = 2.0;
+/// Share of the overlap resolved per second; capped at the full overlap per tick
+const SEPARATION_STRENGTH: f32 = 12.0;
+/// Smallest grid cell size; cells grow past it to fit the largest enemies
+const MIN_CELL_SIZE: f32 = 50.0;
+
+fn cell_of(pos: &Point2D, cell_size: f32) -> (i32, i32) {
+    (
+        (pos.x / cell_size).floor() as i32,
+        (pos.y / cell_size).floor() as i32,
+    )
+}
+
+/// Pushes overlapping enemies apart so crowds spread around the shield
+/// instead of stacking. Larger enemies (by area) displace smaller ones more
+/// than the reverse. Pairs are found through a uniform grid, so each enemy
+/// only checks the enemies in its own and adjacent cells.
+///
+/// Separation never pulls an enemy closer to the center than its stop distance.
+pub fn separate(enemies: &mut [Enemy], center: &Point2D, shield_radius: Option<f32>, dt: f32) {
+    // A cell spans at least the largest pair of radii, so overlapping
+    // enemies are never more than one cell apart, user presets included
+    let max_radius = enemies.iter().map(|e| e.radius).fold(0.0, f32::max);
+    let cell_size = (2.0 * max_radius + SEPARATION_PADDING).max(MIN_CELL_SIZE);
+
+    let mut grid: HashMap<(i32, i32), Vec<usize>> = HashMap::new();
+    for (i, enemy) in enemies.iter().enumerate() {
+        grid.entry(cell_of(&enemy.position, cell_size))
+            .or_default()
+            .push(i);
+    }
+
+    let mut pushes = vec![(0.0f32, 0.0f32); enemies.len()];
+    for (i, enemy) in enemies.iter().enumerate() {
+        let (cx, cy) = cell_of(&enemy.position, cell_size);
+        for nx in cx - 1..=cx + 1 {
+            for ny in cy - 1..=cy + 1 {
+                let Some(cell) = grid.get(&(nx, ny)) else {
+                    continue;
+                };
+                // Each pair is handled once, from its lower index
+                for &j in cell.iter().filter(|&&j| j > i) {
+                    let other = &enemies[j];
+                    let min_dist = enemy.radius + other.radius + SEPARATION_PADDING;
+                    let mut dx = enemy.position.x - other.position.x;
+                    let mut dy = enemy.position.y - other.position.y;
+                    let mut dist = (dx * dx + dy * dy).sqrt();
+                    if dist >= min_dist {
+                        continue;
+                    }
+                    if dist < 0.01 {
+                        // Stacked exactly: split along an id-derived direction
+                        let angle = (enemy.id + other.id) as f32;
+                        dx = angle.cos();
+                        dy = angle.sin();
+                        dist = 1.0;
+                    }
+
+                    let overlap = min_dist - dist;
+                    let mass_i = enemy.radius * enemy.radius;
+                    let mass_j = other.radius * other.radius;
+                    let share_i = mass_j / (mass_i + mass_j);
+                    let share_j = mass_i / (mass_i + mass_j);
+                    let (ux, uy) = (dx / dist, dy / dist);
+                    pushes[i].0 += ux * overlap * share_i;
+                    pushes[i].1 += uy * overlap * share_i;
+                    pushes[j].0 -= ux * overlap * share_j;
+                    pushes[j].1 -= uy * overlap * share_j;
+                }
+            }
+        }
+    }
+
+    let strength = (SEPARATION_STRENGTH * dt).min(1.0);
+    for (enemy, (px, py)) in enemies.iter_mut().zip(pushes) {
+        if px == 0.0 && py == 0.0 {
+            continue;
+        }
+        let dist_before = enemy.position.distance_to(center);
+        enemy.position.x += px * strength;
+        enemy.position.y += py * strength;
+
+        let floor = dist_before.min(enemy.stop_distance(shield_radius));
+        let dist_after = enemy.position.distance_to(center);
+        if dist_after < floor && dist_after > 0.01 {
+            let scale = floor / dist_after;
+            enemy.position.x = center.x + (enemy.position.x - center.x) * scale;
+            enemy.position.y = center.y + (enemy.position.y - center.y) * scale;
+        }
+    }
+}
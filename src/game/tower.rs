@@ -6,6 +6,74 @@ use crate::data::tower_defs::{ResolvedAction, TowerKind, UpgradeableProp, get_de
 pub struct NotificationSettings {
     pub shield_broken: bool,
     pub shield_low: bool,
+    pub tower_destroyed: bool,
+}
+
+/// Gold charged per missing HP when repairing a tower
+pub const REPAIR_COST_PER_HP: f32 = 0.5;
+
+/// Which enemies in range a tower shoots at first
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum TargetingMode {
+    #[default]
+    Nearest,
+    /// Closest to the core
+    First,
+    Strongest,
+    Weakest,
+}
+
+impl TargetingMode {
+    pub fn all() -> &'static [TargetingMode] {
+        &[
+            TargetingMode::Nearest,
+            TargetingMode::First,
+            TargetingMode::Strongest,
+            TargetingMode::Weakest,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TargetingMode::Nearest => "Proche",
+            TargetingMode::First => "Premier",
+            TargetingMode::Strongest => "Fort",
+            TargetingMode::Weakest => "Faible",
+        }
+    }
+}
+
+/// Automatic upgrade spending during preparation: upgrades are bought in
+/// priority order (each one maxed before the next) while gold stays above the reserve
+#[derive(Clone)]
+pub struct UpgradePolicy {
+    pub priorities: Vec<&'static str>,
+    pub gold_reserve: u32,
+}
+
+impl UpgradePolicy {
+    pub const RESERVE_STEP: u32 = 50;
+
+    pub fn new(priorities: Vec<&'static str>) -> Self {
+        Self {
+            priorities,
+            gold_reserve: 0,
+        }
+    }
+
+    pub fn move_up(&mut self, index: usize) {
+        if index > 0 && index < self.priorities.len() {
+            self.priorities.swap(index - 1, index);
+        }
+    }
+
+    pub fn increase_reserve(&mut self) {
+        self.gold_reserve += Self::RESERVE_STEP;
+    }
+
+    pub fn decrease_reserve(&mut self) {
+        self.gold_reserve = self.gold_reserve.saturating_sub(Self::RESERVE_STEP);
+    }
 }
 
 #[derive(Clone)]
@@ -19,10 +87,15 @@ pub struct Tower {
     pub attack_speed: UpgradeableProp,
     pub attack_cooldown: f32,
     pub actions: Vec<TowerActionState>,
-    pub base_cost: u32,
+    /// Total gold spent on this tower: placement plus every upgrade bought
+    pub invested: u32,
     pub radius: f32,
+    pub hp: f32,
+    pub max_hp: f32,
     pub gold_accumulator: f32,
     pub notification_settings: Option<NotificationSettings>,
+    pub targeting: TargetingMode,
+    pub upgrade_policy: Option<UpgradePolicy>,
 }
 
 #[derive(Clone)]
@@ -61,6 +134,7 @@ impl Tower {
             Some(NotificationSettings {
                 shield_broken: false,
                 shield_low: false,
+                tower_destroyed: false,
             })
         } else {
             None
@@ -76,10 +150,14 @@ impl Tower {
             attack_speed: def.attack_speed.clone(),
             attack_cooldown: 0.0,
             actions,
-            base_cost: def.base_cost,
+            invested: def.base_cost,
             radius: 14.0,
+            hp: def.max_hp,
+            max_hp: def.max_hp,
             gold_accumulator: 0.0,
             notification_settings,
+            targeting: TargetingMode::default(),
+            upgrade_policy: None,
         }
     }
 
@@ -127,6 +205,45 @@ impl Tower {
         result
     }
 
+    pub fn is_damaged(&self) -> bool {
+        self.hp < self.max_hp
+    }
+
+    /// Gold needed to restore the tower to full HP
+    pub fn repair_cost(&self) -> u32 {
+        ((self.max_hp - self.hp).max(0.0) * REPAIR_COST_PER_HP).ceil() as u32
+    }
+
+    /// Default policy: every upgrade, in `get_upgrades` order
+    pub fn default_upgrade_policy(&self) -> UpgradePolicy {
+        UpgradePolicy::new(
+            self.get_upgrades()
+                .iter()
+                .map(|(_, name, _)| *name)
+                .collect(),
+        )
+    }
+
+    /// Next upgrade wanted by the policy with its cost and the gold reserve to keep
+    pub fn next_policy_upgrade(&self) -> Option<(TowerUpgradeId, u32, u32)> {
+        let policy = self.upgrade_policy.as_ref()?;
+        let upgrades = self.get_upgrades();
+        policy.priorities.iter().find_map(|name| {
+            upgrades
+                .iter()
+                .find(|(_, n, prop)| n == name && prop.can_upgrade())
+                .map(|(uid, _, prop)| (*uid, prop.cost(), policy.gold_reserve))
+        })
+    }
+
+    /// Current level of each upgrade, in `get_upgrades` order
+    pub fn upgrade_levels(&self) -> Vec<u32> {
+        self.get_upgrades()
+            .iter()
+            .map(|(_, _, prop)| prop.current_level)
+            .collect()
+    }
+
     pub fn apply_upgrade(&mut self, upgrade_id: TowerUpgradeId) -> bool {
         match upgrade_id {
             TowerUpgradeId::Range => {
@@ -162,6 +279,28 @@ impl Tower {
         }
     }
 
+    /// Undoes one level of an upgrade, returns false if it was not upgraded
+    pub fn revert_upgrade(&mut self, upgrade_id: TowerUpgradeId) -> bool {
+        let prop = match upgrade_id {
+            TowerUpgradeId::Range => Some(&mut self.range),
+            TowerUpgradeId::AttackSpeed => Some(&mut self.attack_speed),
+            TowerUpgradeId::Action {
+                action_idx,
+                upgrade_idx,
+            } => self
+                .actions
+                .get_mut(action_idx)
+                .and_then(|a| a.upgrades.get_mut(upgrade_idx)),
+        };
+        match prop {
+            Some(prop) if prop.current_level > 0 => {
+                prop.current_level -= 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
     pub fn upgrade_cost(&self, upgrade_id: TowerUpgradeId) -> Option<u32> {
         match upgrade_id {
             TowerUpgradeId::Range => {
@@ -224,7 +363,7 @@ impl Tower {
                 .sum::<u32>()
     }
 
-    pub fn sell_value(&self) -> u32 {
-        self.base_cost * self.level() / 2
+    pub fn sell_value(&self, refund_ratio: f32) -> u32 {
+        (self.invested as f32 * refund_ratio).round() as u32
     }
 }
@@ -1,174 +1,472 @@
 use super::Point2D;
-use super::enemy::{Enemy, EnemyShape};
-use rand::Rng;
+use super::affix::{EliteAffix, roll_affixes};
+use super::enemy::Enemy;
+use crate::data::difficulty::{Mutator, RunSettings};
+use crate::data::enemy_types::{EnemyPreset, get_preset};
+use crate::data::wave_script::{EnemyPick, Formation, SpawnSide, WaveScript};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 
-struct EnemySpawnInfo {
-    shape: EnemyShape,
+/// Share of max HP regenerated per second under `Mutator::EnemyRegen`
+const MUTATOR_REGEN_PER_SECOND: f32 = 0.01;
+/// Seconds before a spawn that its location is known and telegraphed
+pub const TELEGRAPH_LEAD: f32 = 3.0;
+/// Gold per second left on the preparation timer when calling a wave early
+const EARLY_CALL_GOLD_PER_SECOND: f32 = 10.0;
+/// Tags of the per-wave random streams, so draws from one never shift another
+const COMPOSITION_STREAM: u64 = 1;
+const AFFIX_STREAM: u64 = 2;
+const POSITION_STREAM: u64 = 3;
+
+/// Seconds between a boss and its `Mutator::DoubleBosses` copy, which
+/// shares its spawn point and would otherwise sit right on top of it
+const DOUBLE_BOSS_GAP: f32 = 2.0;
+
+/// Point on a map edge rolled without the viewport, mapped onto it when
+/// the enemy appears
+#[derive(Clone, Copy)]
+struct EdgeRoll {
+    side: SpawnSide,
+    /// Position along the edge, from -1 to 1
+    along: f32,
+}
+
+/// Where an enemy appears relative to its group
+enum SpawnPoint {
+    /// Own point on an edge, for scattered groups
+    Edge(EdgeRoll),
+    /// Rank along the group's line
+    Line,
+    /// Offset from the group's anchor
+    Cluster(f32, f32),
+}
+
+/// One enemy of the current wave, waiting for its spawn time
+struct PendingSpawn {
+    /// Seconds after the wave start
+    at: f32,
+    /// Preset id of the enemy to spawn
+    enemy: String,
+    /// Index of its group in `WaveManager::groups`, and its rank in it
+    group: usize,
+    slot: u32,
+    point: SpawnPoint,
+    /// Elite affixes, empty for a regular enemy
+    affixes: Vec<EliteAffix>,
+    /// Mapped onto the viewport `TELEGRAPH_LEAD` seconds ahead so it can be
+    /// shown
+    position: Option<Point2D>,
+}
+
+/// Warning marker for an enemy about to spawn
+#[derive(Clone)]
+pub struct SpawnTelegraph {
+    pub position: Point2D,
+    /// Seconds until the enemy appears
+    pub countdown: f32,
+}
+
+/// Placement of a spawn group; formations share one anchor
+struct ActiveGroup {
+    formation: Formation,
+    count: u32,
+    anchor: EdgeRoll,
+}
+
+/// Composition of a wave rolled ahead of time, shown while preparing
+pub struct WavePreview {
+    pub wave_number: u32,
+    /// Archetypes in order of first appearance, with their count
+    pub enemies: Vec<(&'static EnemyPreset, u32)>,
+    pub has_boss: bool,
+    /// Summed max HP, before elite affixes
+    pub total_hp: f32,
 }
 
 pub struct WaveManager {
     pub current_wave: u32,
-    enemies_to_spawn: Vec<EnemySpawnInfo>,
-    spawn_timer: f32,
-    spawn_interval: f32,
+    pending: Vec<PendingSpawn>,
+    groups: Vec<ActiveGroup>,
+    /// Wave that `pending` was rolled for
+    prepared_wave: u32,
+    preview: Option<WavePreview>,
+    wave_clock: f32,
     pub wave_active: bool,
+    /// Launch the next wave when the preparation timer runs out; otherwise
+    /// wait for the player to call it
+    pub auto_start: bool,
     between_waves_timer: f32,
     between_waves_duration: f32,
     next_enemy_id: usize,
+    settings: RunSettings,
+    script: WaveScript,
+    /// Seeds every wave roll, kept for the run history
+    pub seed: u64,
 }
 
 impl WaveManager {
-    pub fn new() -> Self {
-        Self {
+    /// A `seed` makes the rolled waves (composition, positions, elites) the
+    /// same on every run using it, whatever the frame rate or viewport; a
+    /// random one is drawn otherwise
+    pub fn new(settings: &RunSettings, seed: Option<u64>, script: WaveScript) -> Self {
+        let seed = seed.unwrap_or_else(rand::random);
+        let mut manager = Self {
             current_wave: 0,
-            enemies_to_spawn: Vec::new(),
-            spawn_timer: 0.0,
-            spawn_interval: 0.5,
+            pending: Vec::new(),
+            groups: Vec::new(),
+            prepared_wave: 0,
+            preview: None,
+            wave_clock: 0.0,
             wave_active: false,
-            between_waves_timer: 5.0,
-            between_waves_duration: 5.0,
+            auto_start: true,
+            between_waves_timer: settings.difficulty.between_waves_duration(),
+            between_waves_duration: settings.difficulty.between_waves_duration(),
             next_enemy_id: 0,
-        }
+            settings: settings.clone(),
+            script,
+            seed,
+        };
+        manager.load_wave(1);
+        manager
     }
 
     pub fn start_next_wave(&mut self) {
         self.current_wave += 1;
-        self.enemies_to_spawn = self.generate_wave(self.current_wave);
+        if self.prepared_wave != self.current_wave {
+            self.load_wave(self.current_wave);
+        }
         self.wave_active = true;
-        self.spawn_timer = 0.0;
+        self.wave_clock = 0.0;
+    }
+
+    /// Makes `wave_number` the next wave, skipping the ones before it
+    pub fn skip_to_wave(&mut self, wave_number: u32) {
+        self.current_wave = wave_number.saturating_sub(1);
+        self.load_wave(wave_number.max(1));
+    }
+
+    /// Random stream `tag` of a wave, only drawn from by `load_wave`
+    fn stream(&self, wave_number: u32, tag: u64) -> StdRng {
+        let key = (u64::from(wave_number) << 8 | tag).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+        StdRng::seed_from_u64(self.seed ^ key)
+    }
+
+    /// Expands the groups of a wave into its spawn timeline, rolling every
+    /// enemy now (kind, spawn point, affixes) so the preview matches what
+    /// will spawn
+    fn load_wave(&mut self, wave_number: u32) {
+        let mut rng = self.stream(wave_number, COMPOSITION_STREAM);
+        let mut affix_rng = self.stream(wave_number, AFFIX_STREAM);
+        let mut position_rng = self.stream(wave_number, POSITION_STREAM);
+        let wave = self.script.wave(wave_number, &mut rng);
+        self.prepared_wave = wave_number;
+        self.pending.clear();
+        self.groups.clear();
+        let double_bosses = self.settings.has(Mutator::DoubleBosses);
+        let pattern_sides = wave.pattern.roll_sides(&mut rng);
+        for (group_idx, group) in wave.groups.iter().enumerate() {
+            let follows_pattern = group.side == SpawnSide::Random && !pattern_sides.is_empty();
+            let group_side = if follows_pattern {
+                pattern_sides[group_idx % pattern_sides.len()]
+            } else {
+                group.side
+            };
+            let boss_group = matches!(&group.enemy, EnemyPick::Id(id)
+                if get_preset(id).is_some_and(|p| p.is_boss));
+            let count = if boss_group {
+                group.count
+            } else {
+                let scaled = group.count as f32 * self.settings.difficulty.wave_size_multiplier();
+                (scaled.round() as u32).max(1)
+            };
+            for slot in 0..count {
+                let enemy = group.enemy.roll(&mut rng).to_string();
+                let copies = if double_bosses && get_preset(&enemy).is_some_and(|p| p.is_boss) {
+                    2
+                } else {
+                    1
+                };
+                // Scattered enemies of a pattern wave rotate through its sides
+                let side = if follows_pattern {
+                    pattern_sides[slot as usize % pattern_sides.len()]
+                } else {
+                    group_side
+                };
+                for copy in 0..copies {
+                    let point = match group.formation {
+                        Formation::Scattered => {
+                            SpawnPoint::Edge(roll_edge(side, &mut position_rng))
+                        }
+                        Formation::Line { .. } => SpawnPoint::Line,
+                        Formation::Cluster { radius } => {
+                            let angle = position_rng.gen_range(0.0..std::f32::consts::TAU);
+                            let dist = radius * position_rng.r#gen::<f32>().sqrt();
+                            SpawnPoint::Cluster(angle.cos() * dist, angle.sin() * dist)
+                        }
+                    };
+                    self.pending.push(PendingSpawn {
+                        at: group.delay
+                            + group.interval * slot as f32
+                            + DOUBLE_BOSS_GAP * copy as f32,
+                        enemy: enemy.clone(),
+                        group: group_idx,
+                        slot,
+                        point,
+                        affixes: roll_affixes(wave_number, &mut affix_rng),
+                        position: None,
+                    });
+                }
+            }
+            self.groups.push(ActiveGroup {
+                formation: group.formation,
+                count,
+                anchor: roll_edge(group_side, &mut position_rng),
+            });
+        }
+        // Stable sort: simultaneous spawns keep their script order
+        self.pending
+            .sort_by(|a, b| a.at.partial_cmp(&b.at).unwrap_or(std::cmp::Ordering::Equal));
+        self.preview = Some(self.build_preview(wave_number));
+    }
+
+    fn build_preview(&self, wave_number: u32) -> WavePreview {
+        let mut preview = WavePreview {
+            wave_number,
+            enemies: Vec::new(),
+            has_boss: false,
+            total_hp: 0.0,
+        };
+        for spawn in &self.pending {
+            let Some(preset) = get_preset(&spawn.enemy) else {
+                continue;
+            };
+            match preview.enemies.iter_mut().find(|(p, _)| p.id == preset.id) {
+                Some((_, count)) => *count += 1,
+                None => preview.enemies.push((preset, 1)),
+            }
+            preview.has_boss |= preset.is_boss;
+            let mut enemy = Enemy::new(0, preset, wave_number, Point2D::zero());
+            self.apply_settings(&mut enemy);
+            preview.total_hp += enemy.max_hp;
+        }
+        preview
+    }
+
+    /// The upcoming wave, available between waves
+    pub fn preview(&self) -> Option<&WavePreview> {
+        self.preview.as_ref().filter(|_| !self.wave_active)
     }
 
     pub fn start_between_waves(&mut self) {
         self.wave_active = false;
         self.between_waves_timer = self.between_waves_duration;
+        self.load_wave(self.current_wave + 1);
     }
 
-    pub fn tick_between_waves(&mut self, dt: f32) -> bool {
+    pub fn tick_between_waves(&mut self, dt: f32, viewport: (f32, f32)) -> bool {
         self.between_waves_timer -= dt;
-        self.between_waves_timer <= 0.0
+        self.place_upcoming(viewport);
+        self.auto_start && self.between_waves_timer <= 0.0
+    }
+
+    /// Seconds left before the next wave launches on its own
+    pub fn time_to_next_wave(&self) -> f32 {
+        self.between_waves_timer.max(0.0)
+    }
+
+    /// Gold for calling the next wave now instead of waiting the timer out
+    pub fn early_call_bonus(&self) -> u32 {
+        (self.time_to_next_wave() * EARLY_CALL_GOLD_PER_SECOND).round() as u32
+    }
+
+    /// Seconds until a pending spawn; the wave starts when the timer runs out
+    fn time_until(&self, at: f32) -> f32 {
+        if self.wave_active {
+            at - self.wave_clock
+        } else {
+            at + self.between_waves_timer.max(0.0)
+        }
+    }
+
+    /// Fixes the spawn point of every enemy due within `TELEGRAPH_LEAD`
+    fn place_upcoming(&mut self, viewport: (f32, f32)) {
+        for i in 0..self.pending.len() {
+            if self.time_until(self.pending[i].at) > TELEGRAPH_LEAD {
+                break;
+            }
+            if self.pending[i].position.is_none() {
+                let position = self.spawn_position(&self.pending[i], viewport);
+                self.pending[i].position = Some(position);
+            }
+        }
+    }
+
+    /// Spawn points known in advance, for the canvas warning markers
+    pub fn telegraphs(&self) -> Vec<SpawnTelegraph> {
+        self.pending
+            .iter()
+            .filter_map(|p| {
+                p.position.as_ref().map(|position| SpawnTelegraph {
+                    position: position.clone(),
+                    countdown: self.time_until(p.at),
+                })
+            })
+            .collect()
     }
 
     pub fn is_wave_complete(&self) -> bool {
-        self.wave_active && self.enemies_to_spawn.is_empty()
+        self.wave_active && self.pending.is_empty()
     }
 
     pub fn tick(&mut self, dt: f32, viewport: (f32, f32)) -> Vec<Enemy> {
-        if !self.wave_active || self.enemies_to_spawn.is_empty() {
+        if !self.wave_active || self.pending.is_empty() {
             return Vec::new();
         }
 
-        self.spawn_timer -= dt;
-        if self.spawn_timer > 0.0 {
-            return Vec::new();
+        self.wave_clock += dt;
+        self.place_upcoming(viewport);
+        let due = self
+            .pending
+            .iter()
+            .take_while(|p| p.at <= self.wave_clock)
+            .count();
+
+        let mut spawned = Vec::new();
+        for spawn in self.pending.drain(..due).collect::<Vec<_>>() {
+            let spawn_pos = match spawn.position.clone() {
+                Some(position) => position,
+                None => self.spawn_position(&spawn, viewport),
+            };
+            let Some(mut enemy) = self.spawn_minion(&spawn.enemy, spawn_pos) else {
+                continue;
+            };
+            if !spawn.affixes.is_empty() {
+                enemy.make_elite(spawn.affixes);
+            }
+            spawned.push(enemy);
         }
+        spawned
+    }
 
-        self.spawn_timer = self.spawn_interval;
+    /// Maps a rolled spawn point onto the viewport
+    fn spawn_position(&self, spawn: &PendingSpawn, viewport: (f32, f32)) -> Point2D {
+        let group = &self.groups[spawn.group];
+        let anchor = edge_position(group.anchor, viewport);
+        let (offset_x, offset_y) = match (&spawn.point, group.formation) {
+            (SpawnPoint::Edge(edge), _) => return edge_position(*edge, viewport),
+            (SpawnPoint::Line, Formation::Line { spacing }) => {
+                // Spread along the tangent of the core direction
+                let len = (anchor.x * anchor.x + anchor.y * anchor.y).sqrt().max(1.0);
+                let rank = spawn.slot as f32 - (group.count as f32 - 1.0) / 2.0;
+                (
+                    -anchor.y / len * spacing * rank,
+                    anchor.x / len * spacing * rank,
+                )
+            }
+            (SpawnPoint::Cluster(x, y), _) => (*x, *y),
+            (SpawnPoint::Line, _) => (0.0, 0.0),
+        };
+        Point2D::new(anchor.x + offset_x, anchor.y + offset_y)
+    }
 
-        let info = self.enemies_to_spawn.remove(0);
-        let spawn_pos = self.random_edge_position(viewport);
+    /// Builds an enemy of preset `enemy` at the current wave's strength, also
+    /// used outside the wave queue (summons, splits). None for an unknown id.
+    pub fn spawn_minion(&mut self, enemy: &str, position: Point2D) -> Option<Enemy> {
+        let preset = get_preset(enemy)?;
         let id = self.next_enemy_id;
         self.next_enemy_id += 1;
+        let mut enemy = Enemy::new(id, preset, self.current_wave, position);
+        self.apply_settings(&mut enemy);
+        Some(enemy)
+    }
 
-        vec![Enemy::new(id, info.shape, self.current_wave, spawn_pos)]
+    /// Difficulty and mutator adjustments on a freshly built enemy
+    fn apply_settings(&self, enemy: &mut Enemy) {
+        let difficulty = self.settings.difficulty;
+        enemy.max_hp *= difficulty.enemy_hp_multiplier();
+        enemy.hp = enemy.max_hp;
+        enemy.speed *= difficulty.enemy_speed_multiplier();
+        if self.settings.has(Mutator::EnemyRegen) {
+            enemy.regen += MUTATOR_REGEN_PER_SECOND;
+        }
     }
+}
 
-    fn generate_wave(&self, wave_num: u32) -> Vec<EnemySpawnInfo> {
-        let mut enemies = Vec::new();
-        // More enemies: starts at 8, grows by 3 per wave
-        let count = 8 + wave_num * 3;
-
-        for _ in 0..count {
-            let shape = if wave_num <= 2 {
-                // Waves 1-2: mostly scouts
-                if rand::thread_rng().r#gen::<f32>() < 0.85 {
-                    EnemyShape::Triangle
-                } else {
-                    EnemyShape::Square
-                }
-            } else if wave_num <= 4 {
-                // Waves 3-4: introduce soldiers
-                let r: f32 = rand::thread_rng().r#gen();
-                if r < 0.5 {
-                    EnemyShape::Triangle
-                } else {
-                    EnemyShape::Square
-                }
-            } else if wave_num <= 7 {
-                // Waves 5-7: introduce tanks
-                let r: f32 = rand::thread_rng().r#gen();
-                if r < 0.35 {
-                    EnemyShape::Triangle
-                } else if r < 0.65 {
-                    EnemyShape::Square
-                } else {
-                    EnemyShape::Pentagon
-                }
-            } else if wave_num <= 10 {
-                // Waves 8-10: introduce destructors
-                let r: f32 = rand::thread_rng().r#gen();
-                if r < 0.25 {
-                    EnemyShape::Triangle
-                } else if r < 0.50 {
-                    EnemyShape::Square
-                } else if r < 0.75 {
-                    EnemyShape::Pentagon
-                } else {
-                    EnemyShape::Hexagon
-                }
-            } else if wave_num <= 15 {
-                // Waves 11-15: balanced mix
-                let r: f32 = rand::thread_rng().r#gen();
-                if r < 0.20 {
-                    EnemyShape::Triangle
-                } else if r < 0.40 {
-                    EnemyShape::Square
-                } else if r < 0.65 {
-                    EnemyShape::Pentagon
-                } else {
-                    EnemyShape::Hexagon
-                }
-            } else {
-                // Waves 16+: heavy mix, fewer scouts
-                let r: f32 = rand::thread_rng().r#gen();
-                if r < 0.10 {
-                    EnemyShape::Triangle
-                } else if r < 0.30 {
-                    EnemyShape::Square
-                } else if r < 0.60 {
-                    EnemyShape::Pentagon
-                } else {
-                    EnemyShape::Hexagon
-                }
-            };
+/// Resolves a random side and rolls a point along it
+fn roll_edge(side: SpawnSide, rng: &mut impl Rng) -> EdgeRoll {
+    let side = match side {
+        SpawnSide::Random => match rng.gen_range(0..4) {
+            0 => SpawnSide::Top,
+            1 => SpawnSide::Bottom,
+            2 => SpawnSide::Left,
+            _ => SpawnSide::Right,
+        },
+        other => other,
+    };
+    EdgeRoll {
+        side,
+        along: rng.gen_range(-1.0..1.0),
+    }
+}
 
-            enemies.push(EnemySpawnInfo { shape });
-        }
+/// Spawn point on the rolled edge, just outside the visible area
+fn edge_position(edge: EdgeRoll, viewport: (f32, f32)) -> Point2D {
+    let half_w = viewport.0 / 2.0;
+    let half_h = viewport.1 / 2.0;
+    let margin = 30.0;
+
+    match edge.side {
+        SpawnSide::Top => Point2D::new(edge.along * half_w, -(half_h + margin)),
+        SpawnSide::Bottom => Point2D::new(edge.along * half_w, half_h + margin),
+        SpawnSide::Left => Point2D::new(-(half_w + margin), edge.along * half_h),
+        SpawnSide::Right | SpawnSide::Random => Point2D::new(half_w + margin, edge.along * half_h),
+        SpawnSide::Point { x, y } => Point2D::new(x, y),
+    }
+}
 
-        // Boss every 5 waves (starting wave 5)
-        if wave_num >= 5 && wave_num % 5 == 0 {
-            let boss_count = wave_num / 10 + 1;
-            for _ in 0..boss_count {
-                enemies.push(EnemySpawnInfo {
-                    shape: EnemyShape::Octagon,
-                });
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every enemy of the next wave as (id, max HP, position, affixes)
+    fn spawned(manager: &mut WaveManager, dt: f32) -> Vec<(usize, f32, f32, f32, Vec<EliteAffix>)> {
+        manager.start_next_wave();
+        let mut enemies = Vec::new();
+        while !manager.is_wave_complete() {
+            for enemy in manager.tick(dt, (1280.0, 720.0)) {
+                enemies.push((
+                    enemy.id,
+                    enemy.max_hp,
+                    enemy.position.x,
+                    enemy.position.y,
+                    enemy.affixes,
+                ));
             }
         }
-
         enemies
     }
 
-    fn random_edge_position(&self, viewport: (f32, f32)) -> Point2D {
-        let mut rng = rand::thread_rng();
-        let half_w = viewport.0 / 2.0;
-        let half_h = viewport.1 / 2.0;
-        let margin = 30.0;
-
-        let side: u8 = rng.gen_range(0..4);
-        match side {
-            0 => Point2D::new(rng.gen_range(-half_w..half_w), -(half_h + margin)), // Top
-            1 => Point2D::new(rng.gen_range(-half_w..half_w), half_h + margin),    // Bottom
-            2 => Point2D::new(-(half_w + margin), rng.gen_range(-half_h..half_h)), // Left
-            _ => Point2D::new(half_w + margin, rng.gen_range(-half_h..half_h)),    // Right
+    #[test]
+    fn seeded_waves_ignore_the_frame_rate() {
+        let settings = RunSettings::default();
+        let mut smooth = WaveManager::new(&settings, Some(42), WaveScript::builtin());
+        let mut choppy = WaveManager::new(&settings, Some(42), WaveScript::builtin());
+        // Late enough for elites
+        smooth.skip_to_wave(20);
+        choppy.skip_to_wave(20);
+        // Telegraphs placed between waves must not shift any roll either
+        for _ in 0..10 {
+            choppy.tick_between_waves(0.5, (1280.0, 720.0));
         }
+
+        let smooth_spawns = spawned(&mut smooth, 1.0 / 60.0);
+        let choppy_spawns = spawned(&mut choppy, 0.25);
+        assert!(!smooth_spawns.is_empty());
+        assert!(
+            smooth_spawns
+                .iter()
+                .any(|(.., affixes)| !affixes.is_empty())
+        );
+        assert_eq!(smooth_spawns, choppy_spawns);
     }
 }
@@ -2,6 +2,7 @@ pub mod shapes;
 
 use gpui::*;
 
+use crate::data::tower_defs::get_def;
 use crate::game::GameState;
 use crate::game::Point2D;
 use crate::game::elemental::TowerElement;
@@ -17,16 +18,24 @@ pub fn render_game(
     game: &GameState,
     _viewport_size: Size<Pixels>,
     placement_preview: Option<PlacementPreview>,
+    selection_box: Option<(Point2D, Point2D)>,
 ) -> impl IntoElement {
     let player = game.player.clone();
     let shield = game.shield.clone();
     let towers = game.towers.clone();
+    let tower_skin = game.save_data.tower_skin;
     let enemies = game.enemies.clone();
     let projectiles = game.projectiles.clone();
     let aoe_splashes = game.aoe_splashes.clone();
     let gold_pulses = game.gold_pulses.clone();
+    let telegraphs = game.wave_manager.telegraphs();
     let elapsed = game.elapsed;
-    let selected_tower = game.selected_tower;
+    let selected_ids = game.selection.ids().to_vec();
+    let blueprint_ghosts: Vec<(Point2D, TowerElement)> = game
+        .unbuilt_blueprint_towers()
+        .into_iter()
+        .map(|t| (Point2D::new(t.x, t.y), get_def(t.kind).element))
+        .collect();
 
     canvas(
         move |_bounds, _window, _cx| {},
@@ -36,22 +45,34 @@ pub fn render_game(
                 bounds.origin.y + bounds.size.height / 2.0,
             );
 
-            // Draw selected tower range circle
-            if let Some(sel) = selected_tower {
-                if let Some(tower) = towers.get(sel) {
-                    draw_circle_outline(
-                        window,
-                        center,
-                        &tower.position,
-                        tower.attack_range(),
-                        Hsla {
-                            h: 0.0,
-                            s: 0.0,
-                            l: 0.5,
-                            a: 0.3,
-                        },
-                    );
-                }
+            // Draw selected towers range circles
+            for tower in towers.iter().filter(|t| selected_ids.contains(&t.id)) {
+                draw_circle_outline(
+                    window,
+                    center,
+                    &tower.position,
+                    tower.attack_range(),
+                    Hsla {
+                        h: 0.0,
+                        s: 0.0,
+                        l: 0.5,
+                        a: 0.3,
+                    },
+                );
+                // Selection marker
+                draw_dashed_circle_outline(
+                    window,
+                    center,
+                    &tower.position,
+                    tower.radius + 6.0,
+                    Hsla {
+                        h: 0.0,
+                        s: 0.0,
+                        l: 1.0,
+                        a: 0.7,
+                    },
+                    8,
+                );
             }
 
             // Draw player range circle
@@ -68,9 +89,14 @@ pub fn render_game(
                 },
             );
 
+            // Draw blueprint ghosts (unbuilt planned towers)
+            for (pos, element) in &blueprint_ghosts {
+                draw_blueprint_ghost(window, center, pos, *element);
+            }
+
             // Draw towers
             for tower in &towers {
-                draw_tower(window, center, tower);
+                draw_tower(window, center, tower, tower_skin);
             }
 
             // Draw gold pulses
@@ -138,6 +164,20 @@ pub fn render_game(
                     48,
                 );
             }
+
+            // Draw upcoming spawn warnings on the canvas edge
+            let half_size = (
+                f32::from(bounds.size.width) / 2.0,
+                f32::from(bounds.size.height) / 2.0,
+            );
+            for telegraph in &telegraphs {
+                draw_spawn_telegraph(window, center, half_size, telegraph, elapsed);
+            }
+
+            // Draw box selection
+            if let Some((ref start, ref end)) = selection_box {
+                draw_selection_box(window, center, start, end);
+            }
         },
     )
     .size_full()
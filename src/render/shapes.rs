@@ -1,11 +1,16 @@
 use gpui::*;
 
+use crate::data::achievements::TowerSkin;
+use crate::data::enemy_types::get_preset;
 use crate::game::AoeSplash;
 use crate::game::GoldPulse;
 use crate::game::Shield;
+use crate::game::ability::{BLINK_TRAIL_DURATION, EnemyAbility};
+use crate::game::elemental::TowerElement;
 use crate::game::enemy::Enemy;
 use crate::game::player::Player;
 use crate::game::tower::Tower;
+use crate::game::wave::{SpawnTelegraph, TELEGRAPH_LEAD};
 use crate::game::{PROJECTILE_FADE_DURATION, Point2D, Projectile, ProjectileSource};
 
 fn to_screen(center: Point<Pixels>, game_pos: &Point2D) -> Point<Pixels> {
@@ -186,27 +191,138 @@ pub fn draw_player(window: &mut Window, center: Point<Pixels>, player: &Player)
 
 pub fn draw_enemy(window: &mut Window, center: Point<Pixels>, enemy: &Enemy, elapsed: f32) {
     let screen_pos = to_screen(center, &enemy.position);
-    let sides = enemy.shape.sides();
-
-    let color = if enemy.is_boss {
-        Hsla {
-            h: 0.0,
-            s: 0.8,
-            l: 0.3,
-            a: 1.0,
-        }
-    } else {
-        Hsla {
-            h: 0.0,
-            s: 0.7,
-            l: 0.5,
-            a: 1.0,
-        }
-    };
+    let sides = enemy.visual.sides;
+    let color = enemy.visual.color();
 
     let rotation = (enemy.id as f32) * 0.5;
+
+    // Blink afterimage: faded copy where the enemy teleported from
+    if let Some((from, remaining)) = &enemy.blink_trail {
+        draw_polygon(
+            window,
+            to_screen(center, from),
+            enemy.radius,
+            sides,
+            Hsla {
+                h: 0.78,
+                s: 0.7,
+                l: 0.6,
+                a: 0.5 * remaining / BLINK_TRAIL_DURATION,
+            },
+            rotation,
+        );
+    }
+
+    // Elite auras: one ring per affix behind the body, outermost first
+    let pulse = 1.0 + 0.08 * (elapsed * 4.0).sin();
+    for (i, affix) in enemy.affixes.iter().enumerate().rev() {
+        let radius = enemy.radius * (1.4 + 0.3 * i as f32) * pulse;
+        draw_circle(window, screen_pos, radius, affix.color());
+    }
+
     draw_polygon(window, screen_pos, enemy.radius, sides, color, rotation);
 
+    for state in &enemy.abilities {
+        match &state.ability {
+            // Healer: pulsing green aura over the heal radius
+            EnemyAbility::Healer { radius, .. } => {
+                let radius = *radius;
+                let pulse = 0.5 + 0.5 * (elapsed * 3.0).sin();
+                draw_circle_outline(
+                    window,
+                    center,
+                    &enemy.position,
+                    radius,
+                    Hsla {
+                        h: 0.33,
+                        s: 0.8,
+                        l: 0.5,
+                        a: 0.2 + 0.3 * pulse,
+                    },
+                );
+            }
+            // Splitter: one dark core per child
+            EnemyAbility::Splitter { count, .. } => {
+                let count = *count;
+                for i in 0..count {
+                    let offset_x = (i as f32 - (count as f32 - 1.0) / 2.0) * 6.0;
+                    draw_circle(
+                        window,
+                        point(screen_pos.x + px(offset_x), screen_pos.y),
+                        2.5,
+                        Hsla {
+                            h: 0.0,
+                            s: 0.8,
+                            l: 0.15,
+                            a: 1.0,
+                        },
+                    );
+                }
+            }
+            // Shielded: cyan ring fading as the absorption is used up
+            EnemyAbility::Shielded { .. } => {
+                if enemy.absorb > 0.0 {
+                    draw_circle_outline(
+                        window,
+                        center,
+                        &enemy.position,
+                        enemy.radius + 4.0,
+                        Hsla {
+                            h: 0.5,
+                            s: 0.9,
+                            l: 0.6,
+                            a: 0.3 + 0.7 * enemy.absorb / enemy.max_absorb,
+                        },
+                    );
+                }
+            }
+            // Blinker: violet spark flashing as the next blink gets close
+            EnemyAbility::Blinker { .. } => {
+                let charge = (1.0 - state.cooldown.max(0.0)).max(0.0);
+                draw_circle(
+                    window,
+                    screen_pos,
+                    2.0 + 2.0 * charge,
+                    Hsla {
+                        h: 0.78,
+                        s: 0.8,
+                        l: 0.7,
+                        a: 0.4 + 0.6 * charge,
+                    },
+                );
+            }
+            // Summoner: small shapes orbiting the enemy
+            EnemyAbility::Summoner {
+                enemy: summoned,
+                count,
+                ..
+            } => {
+                let count = *count;
+                let summoned_sides = get_preset(summoned).map_or(3, |p| p.visual.sides);
+                for i in 0..count {
+                    let angle = elapsed * 1.5 + std::f32::consts::TAU * i as f32 / count as f32;
+                    let dist = enemy.radius + 8.0;
+                    draw_polygon(
+                        window,
+                        point(
+                            screen_pos.x + px(dist * angle.cos()),
+                            screen_pos.y + px(dist * angle.sin()),
+                        ),
+                        4.0,
+                        summoned_sides,
+                        Hsla {
+                            h: 0.78,
+                            s: 0.6,
+                            l: 0.55,
+                            a: 0.8,
+                        },
+                        angle,
+                    );
+                }
+            }
+        }
+    }
+
     // Burn indicator: flickering flame particles around the enemy
     if enemy.burn.is_some() {
         let particle_count = 4;
@@ -242,9 +358,26 @@ pub fn draw_enemy(window: &mut Window, center: Point<Pixels>, enemy: &Enemy, ela
     draw_hp_bar(window, screen_pos, enemy.hp, enemy.max_hp, enemy.radius);
 }
 
-pub fn draw_tower(window: &mut Window, center: Point<Pixels>, tower: &Tower) {
+pub fn draw_tower(
+    window: &mut Window,
+    center: Point<Pixels>,
+    tower: &Tower,
+    skin: Option<TowerSkin>,
+) {
     let screen_pos = to_screen(center, &tower.position);
-    let color = tower.element.color();
+    let (color, rim) = skin_colors(skin, tower.element.color());
+
+    // Skins frame the diamond with a slightly larger one
+    if let Some(rim) = rim {
+        draw_polygon(
+            window,
+            screen_pos,
+            tower.radius + 3.0,
+            4,
+            rim,
+            std::f32::consts::PI / 4.0,
+        );
+    }
 
     // Diamond shape (rotated square)
     draw_polygon(
@@ -256,6 +389,11 @@ pub fn draw_tower(window: &mut Window, center: Point<Pixels>, tower: &Tower) {
         std::f32::consts::PI / 4.0,
     );
 
+    // HP bar above, only once enemies have hit it
+    if tower.is_damaged() {
+        draw_hp_bar(window, screen_pos, tower.hp, tower.max_hp, tower.radius);
+    }
+
     // Level dots below
     let dot_count = tower.level().min(5);
     for i in 0..dot_count {
@@ -277,6 +415,82 @@ pub fn draw_tower(window: &mut Window, center: Point<Pixels>, tower: &Tower) {
     }
 }
 
+/// Fill and rim of a tower wearing `skin`, from its element color
+fn skin_colors(skin: Option<TowerSkin>, element: Hsla) -> (Hsla, Option<Hsla>) {
+    match skin {
+        None => (element, None),
+        Some(TowerSkin::Gilded) => (
+            element,
+            Some(Hsla {
+                h: 0.13,
+                s: 0.9,
+                l: 0.55,
+                a: 1.0,
+            }),
+        ),
+        Some(TowerSkin::Obsidian) => (
+            Hsla {
+                h: element.h,
+                s: 0.3,
+                l: 0.15,
+                a: 1.0,
+            },
+            Some(element),
+        ),
+        Some(TowerSkin::Vapor) => (
+            Hsla {
+                l: (element.l + 0.2).min(0.9),
+                a: 0.7,
+                ..element
+            },
+            Some(Hsla {
+                h: 0.0,
+                s: 0.0,
+                l: 1.0,
+                a: 0.35,
+            }),
+        ),
+    }
+}
+
+pub fn draw_blueprint_ghost(
+    window: &mut Window,
+    center: Point<Pixels>,
+    game_pos: &Point2D,
+    element: TowerElement,
+) {
+    let screen_pos = to_screen(center, game_pos);
+    let color = element.color();
+
+    // Faint diamond with a dashed outline, like the placement preview
+    draw_polygon(
+        window,
+        screen_pos,
+        14.0,
+        4,
+        Hsla {
+            h: color.h,
+            s: color.s,
+            l: color.l,
+            a: 0.2,
+        },
+        std::f32::consts::PI / 4.0,
+    );
+    draw_dashed_circle_outline(
+        window,
+        center,
+        game_pos,
+        20.0,
+        Hsla {
+            h: color.h,
+            s: color.s,
+            l: color.l,
+            a: 0.5,
+        },
+        8,
+    );
+}
+
 pub fn draw_projectile(window: &mut Window, center: Point<Pixels>, proj: &Projectile) {
     let screen_pos = to_screen(center, &proj.current_pos);
     let origin_screen = to_screen(center, &proj.origin);
@@ -502,6 +716,78 @@ pub fn draw_shield(window: &mut Window, center: Point<Pixels>, shield: &Shield)
     }
 }
 
+/// Warning triangle pinned to the canvas edge where an enemy is about to
+/// spawn, pointing inward and blinking faster as the spawn gets close
+pub fn draw_spawn_telegraph(
+    window: &mut Window,
+    center: Point<Pixels>,
+    half_size: (f32, f32),
+    telegraph: &SpawnTelegraph,
+    elapsed: f32,
+) {
+    let inset = 14.0;
+    let x = telegraph
+        .position
+        .x
+        .clamp(-half_size.0 + inset, half_size.0 - inset);
+    let y = telegraph
+        .position
+        .y
+        .clamp(-half_size.1 + inset, half_size.1 - inset);
+    let urgency = 1.0 - (telegraph.countdown / TELEGRAPH_LEAD).clamp(0.0, 1.0);
+    let blink = 0.5 + 0.5 * (elapsed * (4.0 + 8.0 * urgency)).sin();
+    let color = Hsla {
+        h: 0.0,
+        s: 0.9,
+        l: 0.55,
+        a: 0.3 + 0.5 * blink,
+    };
+    let inward = (-y).atan2(-x);
+    draw_polygon(
+        window,
+        to_screen(center, &Point2D::new(x, y)),
+        8.0 + 4.0 * urgency,
+        3,
+        color,
+        inward,
+    );
+}
+
+pub fn draw_selection_box(
+    window: &mut Window,
+    center: Point<Pixels>,
+    start: &Point2D,
+    end: &Point2D,
+) {
+    let a = to_screen(center, start);
+    let b = to_screen(center, end);
+    let origin = point(a.x.min(b.x), a.y.min(b.y));
+    let box_size = size((a.x - b.x).abs(), (a.y - b.y).abs());
+
+    window.paint_quad(PaintQuad {
+        bounds: Bounds {
+            origin,
+            size: box_size,
+        },
+        corner_radii: Corners::all(px(0.0)),
+        background: Hsla {
+            h: 0.55,
+            s: 0.6,
+            l: 0.6,
+            a: 0.1,
+        }
+        .into(),
+        border_widths: Edges::all(px(1.0)),
+        border_color: Hsla {
+            h: 0.55,
+            s: 0.6,
+            l: 0.7,
+            a: 0.6,
+        },
+        border_style: BorderStyle::default(),
+    });
+}
+
 fn draw_hp_bar(window: &mut Window, pos: Point<Pixels>, hp: f32, max_hp: f32, radius: f32) {
     let bar_width = radius * 2.0;
     let bar_height: f32 = 3.0;
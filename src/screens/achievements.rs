@@ -0,0 +1,109 @@
+use gpui::*;
+use gpui_component::button::{Button, ButtonVariants};
+use gpui_component::{Sizable, Size, h_flex, v_flex};
+
+use crate::app::{Screen, SentinelsApp};
+use crate::data::SaveData;
+use crate::data::achievements::{Achievement, TowerSkin};
+
+pub fn render(save_data: &SaveData, cx: &mut Context<SentinelsApp>) -> impl IntoElement {
+    let unlocked_count = Achievement::all()
+        .iter()
+        .filter(|a| save_data.has_achievement(**a))
+        .count();
+
+    v_flex()
+        .size_full()
+        .items_center()
+        .gap_4()
+        .p_6()
+        .child(
+            h_flex()
+                .w_full()
+                .items_center()
+                .justify_between()
+                .child(
+                    Button::new("back")
+                        .ghost()
+                        .label("Retour")
+                        .on_click(cx.listener(|app, _, _window, cx| {
+                            app.navigate_to(Screen::Lobby, cx);
+                        })),
+                )
+                .child(div().text_color(rgb(0xcc66ff)).child(format!(
+                    "{}/{} debloques",
+                    unlocked_count,
+                    Achievement::all().len()
+                ))),
+        )
+        .child(div().text_lg().text_color(rgb(0xffffff)).child("Succes"))
+        .child(
+            v_flex()
+                .gap_2()
+                .w_full()
+                .max_w(px(500.))
+                .children(Achievement::all().iter().map(|&achievement| {
+                    let unlocked = save_data.has_achievement(achievement);
+                    h_flex()
+                        .items_center()
+                        .justify_between()
+                        .p_3()
+                        .rounded_md()
+                        .bg(rgb(if unlocked { 0x2a2a4a } else { 0x1f1f35 }))
+                        .child(
+                            v_flex()
+                                .gap_1()
+                                .child(
+                                    div()
+                                        .text_sm()
+                                        .text_color(rgb(if unlocked { 0xffd700 } else { 0x888888 }))
+                                        .child(achievement.name()),
+                                )
+                                .child(
+                                    div()
+                                        .text_xs()
+                                        .text_color(rgb(0x888888))
+                                        .child(achievement.description()),
+                                ),
+                        )
+                        .child(
+                            div()
+                                .text_xs()
+                                .text_color(rgb(if unlocked { 0xcc66ff } else { 0x666666 }))
+                                .child(achievement.reward_label()),
+                        )
+                })),
+        )
+        .child(skin_section(save_data, cx))
+}
+
+/// Picks the look of the towers among the unlocked skins
+fn skin_section(save_data: &SaveData, cx: &mut Context<SentinelsApp>) -> impl IntoElement + use<> {
+    let current = save_data.tower_skin;
+    let options = std::iter::once(None).chain(save_data.unlocked_skins().into_iter().map(Some));
+
+    v_flex()
+        .gap_2()
+        .items_center()
+        .child(
+            div()
+                .text_sm()
+                .text_color(rgb(0xffffff))
+                .child("Apparence des tourelles"),
+        )
+        .child(h_flex().gap_2().children(options.map(|skin| {
+            let button = Button::new(SharedString::from(format!("skin_{:?}", skin)))
+                .label(skin.map_or("Par defaut", |s: TowerSkin| s.name()))
+                .with_size(Size::Small)
+                .on_click(cx.listener(move |app, _, _window, cx| {
+                    app.save_data.tower_skin = skin;
+                    app.save_data.save();
+                    cx.notify();
+                }));
+            if current == skin {
+                button.primary()
+            } else {
+                button.ghost()
+            }
+        })))
+}
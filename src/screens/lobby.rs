@@ -1,13 +1,31 @@
 use gpui::*;
 use gpui_component::button::{Button, ButtonVariants};
-use gpui_component::v_flex;
+use gpui_component::checkbox::Checkbox;
+use gpui_component::{Sizable, Size, h_flex, v_flex};
 
 use crate::app::{Screen, SentinelsApp};
 use crate::data::SaveData;
+use crate::data::daily::DailyChallenge;
+use crate::data::difficulty::{Difficulty, Mutator, RunSettings};
+use crate::data::enemy_types;
+use crate::data::tower_defs::get_def;
+use crate::data::wave_script::WaveScript;
 
-pub fn render(save_data: &SaveData, cx: &mut Context<SentinelsApp>) -> impl IntoElement {
+pub fn render(
+    save_data: &SaveData,
+    profile_name: &str,
+    wave_script_error: Option<&str>,
+    cx: &mut Context<SentinelsApp>,
+) -> impl IntoElement {
     let best_score = save_data.best_score;
     let max_wave = save_data.max_wave;
+    let load_errors: Vec<String> = enemy_types::load_errors()
+        .iter()
+        .cloned()
+        .chain(wave_script_error.map(str::to_string))
+        .collect();
+    let best_settings = save_data.best_score_settings.as_ref().map(|s| s.label());
+    let settings = save_data.run_settings.clone();
 
     v_flex()
         .size_full()
@@ -15,10 +33,40 @@ pub fn render(save_data: &SaveData, cx: &mut Context<SentinelsApp>) -> impl Into
         .justify_center()
         .gap_6()
         .child(div().text_xl().text_color(rgb(0xffffff)).child("SENTINELS"))
+        .child(
+            h_flex()
+                .gap_2()
+                .items_center()
+                .child(
+                    div()
+                        .text_sm()
+                        .text_color(rgb(0xffd700))
+                        .child(profile_name.to_string()),
+                )
+                .child(
+                    Button::new("change_profile")
+                        .ghost()
+                        .label("Changer de profil")
+                        .with_size(Size::Small)
+                        .on_click(cx.listener(|app, _, _window, cx| {
+                            app.navigate_to(Screen::Welcome, cx);
+                        })),
+                ),
+        )
         .child(div().text_sm().text_color(rgb(0xaaaaaa)).child(format!(
             "Meilleur score: {} | Vague max: {}",
             best_score, max_wave
         )))
+        .children(best_settings.map(|label| div().text_xs().text_color(rgb(0x888888)).child(label)))
+        .children((!load_errors.is_empty()).then(|| {
+            v_flex().items_center().children(
+                load_errors
+                    .into_iter()
+                    .map(|error| div().text_xs().text_color(rgb(0xff4444)).child(error)),
+            )
+        }))
+        .child(run_settings_section(&settings, cx))
+        .child(daily_section(save_data, cx))
         .child(
             v_flex()
                 .gap_3()
@@ -31,6 +79,14 @@ pub fn render(save_data: &SaveData, cx: &mut Context<SentinelsApp>) -> impl Into
                             app.navigate_to(Screen::Play, cx);
                         })),
                 )
+                .child(
+                    Button::new("wave_editor")
+                        .ghost()
+                        .label("Editeur de vagues")
+                        .on_click(cx.listener(|app, _, _window, cx| {
+                            app.navigate_to(Screen::WaveEditor, cx);
+                        })),
+                )
                 .child(
                     Button::new("shop")
                         .ghost()
@@ -39,6 +95,22 @@ pub fn render(save_data: &SaveData, cx: &mut Context<SentinelsApp>) -> impl Into
                             app.navigate_to(Screen::Shop, cx);
                         })),
                 )
+                .child(
+                    Button::new("statistics")
+                        .ghost()
+                        .label("Statistiques")
+                        .on_click(cx.listener(|app, _, _window, cx| {
+                            app.navigate_to(Screen::Statistics, cx);
+                        })),
+                )
+                .child(
+                    Button::new("achievements")
+                        .ghost()
+                        .label("Succes")
+                        .on_click(cx.listener(|app, _, _window, cx| {
+                            app.navigate_to(Screen::Achievements, cx);
+                        })),
+                )
                 .child(
                     Button::new("quit")
                         .danger()
@@ -49,3 +121,120 @@ pub fn render(save_data: &SaveData, cx: &mut Context<SentinelsApp>) -> impl Into
                 ),
         )
 }
+
+fn run_settings_section(
+    settings: &RunSettings,
+    cx: &mut Context<SentinelsApp>,
+) -> impl IntoElement + use<> {
+    let difficulty_buttons = Difficulty::all().iter().map(|&difficulty| {
+        let button = Button::new(SharedString::from(format!("difficulty_{:?}", difficulty)))
+            .label(difficulty.name())
+            .with_size(Size::Small)
+            .on_click(cx.listener(move |app, _, _window, cx| {
+                edit_run_settings(app, |s| s.difficulty = difficulty);
+                cx.notify();
+            }));
+        if settings.difficulty == difficulty {
+            button.primary()
+        } else {
+            button.ghost()
+        }
+    });
+
+    let mutator_rows = Mutator::all().iter().map(|&mutator| {
+        h_flex()
+            .gap_2()
+            .items_center()
+            .child(
+                Checkbox::new(SharedString::from(format!("mutator_{:?}", mutator)))
+                    .checked(settings.has(mutator))
+                    .on_click(cx.listener(move |app, _, _window, cx| {
+                        edit_run_settings(app, |s| s.toggle(mutator));
+                        cx.notify();
+                    })),
+            )
+            .child(
+                v_flex()
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(rgb(0xffffff))
+                            .child(mutator.name()),
+                    )
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(rgb(0x888888))
+                            .child(mutator.description()),
+                    ),
+            )
+    });
+
+    v_flex()
+        .gap_3()
+        .items_center()
+        .child(h_flex().gap_2().children(difficulty_buttons))
+        .child(v_flex().gap_2().children(mutator_rows))
+}
+
+/// Changes the settings of the next run and remembers them
+fn edit_run_settings(app: &mut SentinelsApp, f: impl FnOnce(&mut RunSettings)) {
+    f(&mut app.save_data.run_settings);
+    app.save_data.save();
+}
+
+fn daily_section(save_data: &SaveData, cx: &mut Context<SentinelsApp>) -> impl IntoElement + use<> {
+    let challenge = DailyChallenge::today();
+    let towers: Vec<&str> = challenge
+        .towers
+        .iter()
+        .map(|kind| get_def(*kind).name)
+        .collect();
+    let result = save_data.daily_result(challenge.day).map(|r| {
+        if r.finished {
+            format!("Score classe: {} (vague {})", r.score, r.wave)
+        } else {
+            "Essai classe abandonne".to_string()
+        }
+    });
+    let ranked = result.is_none();
+    let settings = challenge.settings.clone();
+
+    v_flex()
+        .gap_1()
+        .items_center()
+        .p_3()
+        .rounded_md()
+        .bg(rgb(0x2a2a4a))
+        .child(
+            div()
+                .text_sm()
+                .text_color(rgb(0xffd700))
+                .child(format!("Defi du jour - {}", challenge.date_label())),
+        )
+        .child(
+            div()
+                .text_xs()
+                .text_color(rgb(0xcccccc))
+                .child(challenge.settings.label()),
+        )
+        .child(
+            div()
+                .text_xs()
+                .text_color(rgb(0x888888))
+                .child(format!("Tours: {}", towers.join(", "))),
+        )
+        .children(result.map(|r| div().text_xs().text_color(rgb(0xffffff)).child(r)))
+        .child(
+            Button::new("daily")
+                .label(if ranked {
+                    "Jouer le defi"
+                } else {
+                    "Rejouer (non classe)"
+                })
+                .with_size(Size::Small)
+                .on_click(cx.listener(move |app, _, _window, cx| {
+                    app.start_run(settings.clone(), WaveScript::builtin(), cx);
+                })),
+        )
+}
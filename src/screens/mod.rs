@@ -1,4 +1,7 @@
+pub mod achievements;
 pub mod lobby;
 pub mod play;
 pub mod shop;
+pub mod statistics;
+pub mod wave_editor;
 pub mod welcome;
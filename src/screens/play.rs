@@ -5,12 +5,18 @@ use gpui_component::{Sizable, Size, h_flex, v_flex};
 use std::time::{Duration, Instant};
 
 use crate::data::SaveData;
+use crate::data::blueprint::Blueprint;
+use crate::data::difficulty::RunSettings;
 use crate::data::tower_defs::get_def;
+use crate::data::wave_script::WaveScript;
 use crate::game::Point2D;
 use crate::game::{GamePhase, GameState};
 use crate::render::{self, PlacementPreview};
 use crate::ui::hud;
 
+/// Minimum drag distance (game units) before a click becomes a box selection
+const BOX_SELECT_THRESHOLD: f32 = 6.0;
+
 pub enum PlayScreenEvent {
     ReturnToLobby,
 }
@@ -18,19 +24,28 @@ pub enum PlayScreenEvent {
 pub struct PlayScreen {
     pub game_state: GameState,
     pub game_running: bool,
+    pub blueprints: Vec<Blueprint>,
     cursor_pos: Option<Point<Pixels>>,
     loop_started: bool,
+    show_damage_meter: bool,
+    /// Box selection drag, start and current corner in game coordinates
+    drag_start: Option<Point2D>,
+    drag_current: Option<Point2D>,
 }
 
 impl EventEmitter<PlayScreenEvent> for PlayScreen {}
 
 impl PlayScreen {
-    pub fn new(save_data: &SaveData) -> Self {
+    pub fn new(save_data: &SaveData, settings: RunSettings, script: WaveScript) -> Self {
         Self {
-            game_state: GameState::new(save_data),
+            game_state: GameState::new(save_data, settings, script),
             game_running: true,
+            blueprints: Blueprint::load_all(),
             cursor_pos: None,
             loop_started: false,
+            show_damage_meter: true,
+            drag_start: None,
+            drag_current: None,
         }
     }
 
@@ -67,6 +82,50 @@ impl PlayScreen {
         .detach();
     }
 
+    /// Saves the current layout as a new blueprint under ~/.sentinels/blueprints/
+    pub fn save_blueprint(&mut self) {
+        let blueprint = Blueprint::from_towers(Blueprint::unused_name(), &self.game_state.towers);
+        if blueprint.save() {
+            self.blueprints.push(blueprint);
+        }
+    }
+
+    /// Loads a blueprint code from the clipboard, saves it and makes it active
+    pub fn import_blueprint(&mut self, cx: &mut Context<Self>) {
+        let Some(code) = cx.read_from_clipboard().and_then(|item| item.text()) else {
+            return;
+        };
+        let Some(blueprint) = Blueprint::from_code(Blueprint::unused_name(), &code) else {
+            return;
+        };
+        if !blueprint.save() {
+            return;
+        }
+        self.game_state.load_blueprint(&blueprint);
+        self.blueprints.push(blueprint);
+    }
+
+    fn to_game_pos(&self, position: Point<Pixels>) -> Point2D {
+        let sidebar_w = hud::sidebar_width();
+        let viewport_size = self.game_state.viewport_size;
+        let canvas_width = viewport_size.0 - sidebar_w;
+        let canvas_height = viewport_size.1;
+        Point2D::new(
+            f32::from(position.x) - canvas_width / 2.0,
+            f32::from(position.y) - canvas_height / 2.0,
+        )
+    }
+
+    /// Current drag rectangle, once the cursor moved far enough to be a box selection
+    fn selection_box(&self) -> Option<(Point2D, Point2D)> {
+        let start = self.drag_start.clone()?;
+        let current = self.drag_current.clone()?;
+        if start.distance_to(&current) < BOX_SELECT_THRESHOLD {
+            return None;
+        }
+        Some((start, current))
+    }
+
     fn get_placement_preview(&self) -> Option<PlacementPreview> {
         let cursor = self.cursor_pos?;
         let sidebar_w = hud::sidebar_width();
@@ -109,47 +168,85 @@ impl Render for PlayScreen {
         );
 
         let placement_preview = self.get_placement_preview();
-        let game_canvas = render::render_game(&self.game_state, viewport_size, placement_preview);
+        let game_canvas = render::render_game(
+            &self.game_state,
+            viewport_size,
+            placement_preview,
+            self.selection_box(),
+        );
 
         let left_click = cx.listener(|this, event: &MouseDownEvent, _window, _cx| {
-            let sidebar_w = hud::sidebar_width();
-            let viewport_size = this.game_state.viewport_size;
-            let canvas_width = viewport_size.0 - sidebar_w;
-            let canvas_height = viewport_size.1;
-            let center_x = canvas_width / 2.0;
-            let center_y = canvas_height / 2.0;
-            let game_x = f32::from(event.position.x) - center_x;
-            let game_y = f32::from(event.position.y) - center_y;
+            let pos = this.to_game_pos(event.position);
 
             if let Some(tower_idx) = this.game_state.move_mode {
-                this.game_state.try_move_tower(tower_idx, game_x, game_y);
+                this.game_state.try_move_tower(tower_idx, pos.x, pos.y);
             } else if let Some(kind) = this.game_state.placement_mode.take() {
-                this.game_state.try_place_tower(kind, game_x, game_y);
+                this.game_state.try_place_tower(kind, pos.x, pos.y);
+            } else {
+                // Selection happens on release: click or box drag
+                this.drag_start = Some(pos.clone());
+                this.drag_current = Some(pos);
+            }
+        });
+        let left_release = cx.listener(|this, event: &MouseUpEvent, _window, _cx| {
+            let Some(start) = this.drag_start.take() else {
+                return;
+            };
+            this.drag_current = None;
+            let end = this.to_game_pos(event.position);
+            let additive = event.modifiers.shift;
+
+            if start.distance_to(&end) < BOX_SELECT_THRESHOLD {
+                this.game_state.try_select_at(end.x, end.y, additive);
             } else {
-                this.game_state.try_select_at(game_x, game_y);
+                this.game_state.select_in_box(&start, &end, additive);
             }
         });
         let right_click = cx.listener(|this, _, _window, _cx| {
             this.game_state.placement_mode = None;
             this.game_state.move_mode = None;
-            this.game_state.selected_tower = None;
+            this.game_state.selection.clear();
+            this.drag_start = None;
+            this.drag_current = None;
         });
         let mouse_move = cx.listener(|this, event: &MouseMoveEvent, _window, _cx| {
             this.cursor_pos = Some(event.position);
+            if this.drag_start.is_some() {
+                this.drag_current = Some(this.to_game_pos(event.position));
+            }
         });
         let key_down = cx.listener(|this, event: &KeyDownEvent, _window, _cx| {
-            if event.keystroke.key.as_str() == "escape" {
-                this.game_state.placement_mode = None;
-                this.game_state.move_mode = None;
-                this.game_state.selected_tower = None;
+            match event.keystroke.key.as_str() {
+                "escape" => {
+                    this.game_state.placement_mode = None;
+                    this.game_state.move_mode = None;
+                    this.game_state.selection.clear();
+                }
+                "z" if event.keystroke.modifiers.control || event.keystroke.modifiers.platform => {
+                    this.game_state.undo_last_action();
+                }
+                "m" => {
+                    this.show_damage_meter = !this.show_damage_meter;
+                }
+                _ => {}
             }
         });
 
-        let sidebar = hud::render_sidebar(&self.game_state, cx);
+        let sidebar = hud::render_sidebar(&self.game_state, &self.blueprints, cx);
         let speed_buttons = render_speed_buttons(&self.game_state, cx);
+        let damage_meter = self
+            .show_damage_meter
+            .then(|| hud::render_damage_meter(&self.game_state.stats));
+        let damage_numbers = hud::render_damage_numbers(&self.game_state);
+        let event_feed = hud::render_event_feed(&self.game_state.events);
+        let boss_bars = hud::render_boss_bars(&self.game_state.enemies);
+        let boss_banner = hud::render_boss_banner(&self.game_state.events);
+        let achievement_toast = hud::render_achievement_toast(&self.game_state.events);
         let is_game_over = self.game_state.phase == GamePhase::GameOver;
         let score = self.game_state.economy.score;
         let wave = self.game_state.economy.wave_number;
+        let settings_label = self.game_state.settings.label();
+        let run_summary = is_game_over.then(|| hud::render_run_summary(&self.game_state.stats));
 
         div()
             .size_full()
@@ -166,9 +263,16 @@ impl Render for PlayScreen {
                             .relative()
                             .child(game_canvas)
                             .on_mouse_down(MouseButton::Left, left_click)
+                            .on_mouse_up(MouseButton::Left, left_release)
                             .on_mouse_down(MouseButton::Right, right_click)
                             .on_mouse_move(mouse_move)
-                            .child(speed_buttons),
+                            .child(damage_numbers)
+                            .child(speed_buttons)
+                            .child(event_feed)
+                            .when_some(boss_bars, |this, bars| this.child(bars))
+                            .when_some(boss_banner, |this, banner| this.child(banner))
+                            .when_some(achievement_toast, |this, toast| this.child(toast))
+                            .when_some(damage_meter, |this, meter| this.child(meter)),
                     )
                     .child(sidebar)
                     .on_key_down(key_down),
@@ -202,6 +306,13 @@ impl Render for PlayScreen {
                                         .text_color(rgb(0xcccccc))
                                         .child(format!("Score: {} | Vague: {}", score, wave)),
                                 )
+                                .child(
+                                    div()
+                                        .text_xs()
+                                        .text_color(rgb(0x888888))
+                                        .child(settings_label),
+                                )
+                                .when_some(run_summary, |this, summary| this.child(summary))
                                 .child(
                                     Button::new("back_lobby")
                                         .danger()
@@ -0,0 +1,242 @@
+use gpui::*;
+use gpui_component::button::{Button, ButtonVariants};
+use gpui_component::{h_flex, v_flex};
+
+use crate::app::{Screen, SentinelsApp};
+use crate::data::difficulty::Difficulty;
+use crate::data::run_history::RunRecord;
+use crate::data::tower_defs::{TowerKind, get_def};
+
+/// Runs drawn in the score trend chart
+const TREND_RUNS: usize = 20;
+/// Height of the tallest bar of the trend chart
+const TREND_HEIGHT: f32 = 80.0;
+const RECENT_RUNS: usize = 8;
+
+pub fn render(history: &[RunRecord], cx: &mut Context<SentinelsApp>) -> impl IntoElement {
+    let header = h_flex()
+        .w_full()
+        .items_center()
+        .justify_between()
+        .child(
+            Button::new("back")
+                .ghost()
+                .label("Retour")
+                .on_click(cx.listener(|app, _, _window, cx| {
+                    app.navigate_to(Screen::Lobby, cx);
+                })),
+        )
+        .child(
+            div()
+                .text_color(rgb(0xaaaaaa))
+                .child(format!("{} parties", history.len())),
+        );
+
+    let content = if history.is_empty() {
+        div()
+            .text_sm()
+            .text_color(rgb(0x888888))
+            .child("Aucune partie terminee pour l'instant")
+            .into_any_element()
+    } else {
+        v_flex()
+            .gap_4()
+            .w_full()
+            .max_w(px(640.))
+            .child(career_section(history))
+            .child(trend_section(history))
+            .child(
+                h_flex()
+                    .gap_4()
+                    .items_start()
+                    .child(bests_section(history))
+                    .child(pick_rate_section(history)),
+            )
+            .child(recent_section(history))
+            .into_any_element()
+    };
+
+    v_flex()
+        .id("statistics")
+        .size_full()
+        .items_center()
+        .gap_4()
+        .p_6()
+        .overflow_y_scroll()
+        .child(header)
+        .child(
+            div()
+                .text_lg()
+                .text_color(rgb(0xffffff))
+                .child("Statistiques"),
+        )
+        .child(content)
+}
+
+fn panel(title: &'static str) -> Div {
+    v_flex()
+        .gap_1()
+        .p_3()
+        .rounded_md()
+        .bg(rgb(0x2a2a4a))
+        .child(div().text_sm().text_color(rgb(0xffd700)).child(title))
+}
+
+fn stat_line(label: &'static str, value: String) -> impl IntoElement {
+    h_flex()
+        .justify_between()
+        .gap_4()
+        .text_xs()
+        .child(div().text_color(rgb(0x888888)).child(label))
+        .child(div().text_color(rgb(0xffffff)).child(value))
+}
+
+fn career_section(history: &[RunRecord]) -> impl IntoElement {
+    let runs = history.len() as u32;
+    let total_time: f32 = history.iter().map(|r| r.duration).sum();
+    let total_gold: u32 = history.iter().map(|r| r.gold_earned).sum();
+    let total_pepites: u32 = history.iter().map(|r| r.pepites).sum();
+    let average_score = history.iter().map(|r| r.score).sum::<u32>() / runs.max(1);
+    let average_wave = history.iter().map(|r| r.wave).sum::<u32>() as f32 / runs.max(1) as f32;
+    let hours = total_time as u32 / 3600;
+    let minutes = total_time as u32 % 3600 / 60;
+
+    panel("Carriere")
+        .child(stat_line(
+            "Temps de jeu",
+            format!("{}h{:02}", hours, minutes),
+        ))
+        .child(stat_line("Or gagne", total_gold.to_string()))
+        .child(stat_line("Pepites gagnees", total_pepites.to_string()))
+        .child(stat_line("Score moyen", average_score.to_string()))
+        .child(stat_line("Vague moyenne", format!("{:.1}", average_wave)))
+}
+
+/// Score of the last runs as bars, with the average of the last ten runs
+/// compared to the ten before
+fn trend_section(history: &[RunRecord]) -> impl IntoElement {
+    let recent = &history[history.len().saturating_sub(TREND_RUNS)..];
+    let top = recent.iter().map(|r| r.score).max().unwrap_or(0).max(1);
+    let bars = recent.iter().map(|record| {
+        let height = TREND_HEIGHT * record.score as f32 / top as f32;
+        div()
+            .w(px(12.))
+            .h(px(height.max(2.0)))
+            .rounded_sm()
+            .bg(rgb(0x4a90e2))
+    });
+
+    let average = |runs: &[RunRecord]| {
+        (!runs.is_empty()).then(|| runs.iter().map(|r| r.score).sum::<u32>() / runs.len() as u32)
+    };
+    let split = history.len().saturating_sub(10);
+    let last_ten = average(&history[split..]);
+    let previous_ten = average(&history[split.saturating_sub(10)..split]);
+    let comparison = match (last_ten, previous_ten) {
+        (Some(last), Some(previous)) => {
+            let delta = last as i64 - previous as i64;
+            let color = if delta >= 0 { 0x44ff44 } else { 0xff4444 };
+            div().text_xs().text_color(rgb(color)).child(format!(
+                "Moyenne des 10 dernieres: {} ({:+} sur les 10 precedentes)",
+                last, delta
+            ))
+        }
+        (Some(last), None) => div()
+            .text_xs()
+            .text_color(rgb(0xcccccc))
+            .child(format!("Moyenne des dernieres parties: {}", last)),
+        _ => div(),
+    };
+
+    panel("Tendance des scores")
+        .child(
+            h_flex()
+                .h(px(TREND_HEIGHT))
+                .items_end()
+                .gap_1()
+                .children(bars),
+        )
+        .child(comparison)
+}
+
+fn bests_section(history: &[RunRecord]) -> impl IntoElement {
+    let rows = Difficulty::all().iter().map(|&difficulty| {
+        let runs: Vec<&RunRecord> = history
+            .iter()
+            .filter(|r| r.settings.difficulty == difficulty)
+            .collect();
+        let value = if runs.is_empty() {
+            "-".to_string()
+        } else {
+            let score = runs.iter().map(|r| r.score).max().unwrap_or(0);
+            let wave = runs.iter().map(|r| r.wave).max().unwrap_or(0);
+            format!("{} / vague {}", score, wave)
+        };
+        stat_line(difficulty.name(), value)
+    });
+
+    panel("Records par difficulte").flex_1().children(rows)
+}
+
+/// Share of runs in which each tower was built at least once
+fn pick_rate_section(history: &[RunRecord]) -> impl IntoElement {
+    let runs = history.len().max(1) as f32;
+    let mut rates: Vec<(TowerKind, f32, u32)> = TowerKind::all()
+        .iter()
+        .map(|&kind| {
+            let picked = history.iter().filter(|r| r.built(kind) > 0).count() as f32;
+            let built = history.iter().map(|r| r.built(kind)).sum();
+            (kind, picked / runs, built)
+        })
+        .collect();
+    rates.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+
+    panel("Tours choisies")
+        .flex_1()
+        .children(rates.into_iter().map(|(kind, rate, built)| {
+            stat_line(
+                get_def(kind).name,
+                format!("{:.0}% ({} posees)", rate * 100.0, built),
+            )
+        }))
+}
+
+fn recent_section(history: &[RunRecord]) -> impl IntoElement {
+    let rows = history.iter().rev().take(RECENT_RUNS).map(|record| {
+        h_flex()
+            .gap_3()
+            .text_xs()
+            .child(
+                div()
+                    .w(px(80.))
+                    .text_color(rgb(0x888888))
+                    .child(record.date_label()),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .text_color(rgb(0xcccccc))
+                    .child(record.settings.label()),
+            )
+            .child(
+                div()
+                    .w(px(110.))
+                    .text_color(rgb(0xffffff))
+                    .child(format!("{} (vague {})", record.score, record.wave)),
+            )
+            .child(
+                div()
+                    .w(px(100.))
+                    .text_color(rgb(0xff8888))
+                    .child(record.cause_of_death.clone()),
+            )
+            .child(
+                div()
+                    .w(px(40.))
+                    .text_color(rgb(0x888888))
+                    .child(record.duration_label()),
+            )
+    });
+
+    panel("Dernieres parties").children(rows)
+}
@@ -0,0 +1,494 @@
+use gpui::prelude::FluentBuilder;
+use gpui::*;
+use gpui_component::button::{Button, ButtonVariants};
+use gpui_component::{Disableable, Sizable, Size, h_flex, v_flex};
+
+use crate::data::enemy_types::{all_presets, get_preset};
+use crate::data::wave_script::{
+    EnemyPick, Formation, SpawnGroup, SpawnPattern, SpawnSide, WaveDef, WaveScript,
+};
+use crate::game::Point2D;
+use crate::game::enemy::Enemy;
+
+/// Distance an enemy walks from its spawn point to the core, for estimates
+const ESTIMATE_TRAVEL_DISTANCE: f32 = 450.0;
+
+pub enum WaveEditorEvent {
+    Back,
+    /// Play the edited script, starting at `wave`
+    TestRun {
+        script: WaveScript,
+        wave: u32,
+    },
+}
+
+pub struct WaveEditorScreen {
+    script: WaveScript,
+    /// Index in `script.waves`
+    selected: usize,
+    status: Option<String>,
+}
+
+impl EventEmitter<WaveEditorEvent> for WaveEditorScreen {}
+
+/// Strength of a wave at normal difficulty, without elites
+struct WaveEstimate {
+    enemy_count: u32,
+    total_hp: f32,
+    /// Seconds until the last enemy spawns
+    duration: f32,
+    /// Damage per second needed to kill everything before the last enemy
+    /// reaches the core
+    dps_needed: f32,
+}
+
+impl WaveEditorScreen {
+    pub fn new(script: WaveScript) -> Self {
+        Self {
+            script,
+            selected: 0,
+            status: None,
+        }
+    }
+
+    fn wave_mut(&mut self) -> Option<&mut WaveDef> {
+        self.status = None;
+        self.script.waves.get_mut(self.selected)
+    }
+
+    fn edit_group(&mut self, group: usize, f: impl FnOnce(&mut SpawnGroup)) {
+        if let Some(group) = self.wave_mut().and_then(|w| w.groups.get_mut(group)) {
+            f(group);
+        }
+    }
+
+    fn add_wave(&mut self) {
+        let wave = self
+            .script
+            .waves
+            .get(self.selected)
+            .cloned()
+            .unwrap_or(WaveDef {
+                groups: Vec::new(),
+                pattern: SpawnPattern::Scattered,
+            });
+        self.selected = (self.selected + 1).min(self.script.waves.len());
+        self.script.waves.insert(self.selected, wave);
+        self.status = None;
+    }
+
+    fn remove_wave(&mut self) {
+        if self.script.waves.len() > 1 {
+            self.script.waves.remove(self.selected);
+            self.selected = self.selected.min(self.script.waves.len() - 1);
+            self.status = None;
+        }
+    }
+
+    fn save(&mut self) {
+        self.status = Some(match self.script.save() {
+            Ok(()) => "Script sauvegarde".to_string(),
+            Err(err) => format!("Erreur: {}", err),
+        });
+    }
+}
+
+impl Render for WaveEditorScreen {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let wave_number = self.selected as u32 + 1;
+        let wave = self.script.waves.get(self.selected).cloned();
+
+        let wave_buttons: Vec<AnyElement> = (0..self.script.waves.len())
+            .map(|i| {
+                let button = Button::new(SharedString::from(format!("wave_{}", i)))
+                    .label(format!("Vague {}", i + 1))
+                    .with_size(Size::Small)
+                    .on_click(cx.listener(move |editor, _, _window, cx| {
+                        editor.selected = i;
+                        cx.notify();
+                    }));
+                if i == self.selected {
+                    button.primary().into_any_element()
+                } else {
+                    button.ghost().into_any_element()
+                }
+            })
+            .collect();
+
+        v_flex()
+            .size_full()
+            .gap_4()
+            .p_6()
+            .child(
+                h_flex()
+                    .w_full()
+                    .items_center()
+                    .justify_between()
+                    .child(
+                        Button::new("back")
+                            .ghost()
+                            .label("Retour")
+                            .on_click(cx.listener(|_, _, _window, cx| {
+                                cx.emit(WaveEditorEvent::Back);
+                            })),
+                    )
+                    .child(
+                        div()
+                            .text_lg()
+                            .text_color(rgb(0xffffff))
+                            .child("Editeur de vagues"),
+                    )
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .items_center()
+                            .when_some(self.status.clone(), |this, status| {
+                                this.child(div().text_xs().text_color(rgb(0xaaaaaa)).child(status))
+                            })
+                            .child(
+                                Button::new("save")
+                                    .label("Sauvegarder")
+                                    .on_click(cx.listener(|editor, _, _window, cx| {
+                                        editor.save();
+                                        cx.notify();
+                                    })),
+                            )
+                            .child(
+                                Button::new("test")
+                                    .primary()
+                                    .label(format!("Tester la vague {}", wave_number))
+                                    .on_click(cx.listener(move |editor, _, _window, cx| {
+                                        cx.emit(WaveEditorEvent::TestRun {
+                                            script: editor.script.clone(),
+                                            wave: wave_number,
+                                        });
+                                    })),
+                            ),
+                    ),
+            )
+            .child(
+                h_flex()
+                    .flex_1()
+                    .w_full()
+                    .gap_4()
+                    .items_start()
+                    // Wave list
+                    .child(
+                        v_flex()
+                            .id("wave_list")
+                            .w(px(140.))
+                            .h_full()
+                            .gap_1()
+                            .overflow_y_scroll()
+                            .children(wave_buttons)
+                            .child(
+                                Button::new("add_wave")
+                                    .label("+ Vague")
+                                    .with_size(Size::Small)
+                                    .on_click(cx.listener(|editor, _, _window, cx| {
+                                        editor.add_wave();
+                                        cx.notify();
+                                    })),
+                            )
+                            .child(
+                                Button::new("remove_wave")
+                                    .danger()
+                                    .label("Supprimer")
+                                    .with_size(Size::Small)
+                                    .disabled(self.script.waves.len() <= 1)
+                                    .on_click(cx.listener(|editor, _, _window, cx| {
+                                        editor.remove_wave();
+                                        cx.notify();
+                                    })),
+                            )
+                            .child(
+                                div()
+                                    .text_xs()
+                                    .text_color(rgb(0x888888))
+                                    .child("Ensuite: generation infinie"),
+                            ),
+                    )
+                    // Selected wave
+                    .when_some(wave, |this, wave| {
+                        this.child(wave_panel(&wave, wave_number, cx))
+                    }),
+            )
+    }
+}
+
+fn wave_panel(
+    wave: &WaveDef,
+    wave_number: u32,
+    cx: &mut Context<WaveEditorScreen>,
+) -> impl IntoElement + use<> {
+    let estimate = estimate_wave(wave, wave_number);
+    let pattern = wave.pattern;
+    let group_rows: Vec<AnyElement> = wave
+        .groups
+        .iter()
+        .enumerate()
+        .map(|(i, group)| group_row(i, group, cx).into_any_element())
+        .collect();
+
+    v_flex()
+        .id("wave_panel")
+        .flex_1()
+        .h_full()
+        .gap_3()
+        .overflow_y_scroll()
+        .child(
+            h_flex()
+                .gap_4()
+                .items_center()
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .items_center()
+                        .child(div().text_xs().text_color(rgb(0xaaaaaa)).child("Schema"))
+                        .child(
+                            Button::new("pattern")
+                                .label(pattern.name())
+                                .with_size(Size::Small)
+                                .on_click(cx.listener(move |editor, _, _window, cx| {
+                                    if let Some(wave) = editor.wave_mut() {
+                                        wave.pattern = pattern.next();
+                                    }
+                                    cx.notify();
+                                })),
+                        ),
+                )
+                .child(estimate_stat(
+                    "Ennemis",
+                    format!("{}", estimate.enemy_count),
+                    rgb(0xffffff),
+                ))
+                .child(estimate_stat(
+                    "PV totaux",
+                    format!("{:.0}", estimate.total_hp),
+                    rgb(0xff8866),
+                ))
+                .child(estimate_stat(
+                    "Duree",
+                    format!("{:.1}s", estimate.duration),
+                    rgb(0xffffff),
+                ))
+                .child(estimate_stat(
+                    "DPS requis",
+                    format!("{:.0}", estimate.dps_needed),
+                    rgb(0xffd700),
+                )),
+        )
+        .children(group_rows)
+        .child(
+            Button::new("add_group")
+                .label("+ Groupe")
+                .with_size(Size::Small)
+                .on_click(cx.listener(|editor, _, _window, cx| {
+                    if let Some(wave) = editor.wave_mut() {
+                        wave.groups.push(SpawnGroup {
+                            enemy: EnemyPick::Id(all_presets()[0].id.clone()),
+                            count: 5,
+                            interval: 0.5,
+                            delay: 0.0,
+                            side: SpawnSide::Random,
+                            formation: Formation::Scattered,
+                        });
+                    }
+                    cx.notify();
+                })),
+        )
+}
+
+fn estimate_stat(label: &'static str, value: String, color: impl Into<Hsla>) -> impl IntoElement {
+    v_flex()
+        .child(div().text_xs().text_color(rgb(0xaaaaaa)).child(label))
+        .child(div().text_sm().text_color(color.into()).child(value))
+}
+
+fn group_row(
+    index: usize,
+    group: &SpawnGroup,
+    cx: &mut Context<WaveEditorScreen>,
+) -> impl IntoElement + use<> {
+    let enemy = group.enemy.clone();
+    let side = group.side;
+    let formation = group.formation;
+
+    h_flex()
+        .gap_3()
+        .items_center()
+        .p_2()
+        .rounded_md()
+        .bg(rgb(0x2a2a4a))
+        .child(
+            Button::new(SharedString::from(format!("group_{}_enemy", index)))
+                .label(pick_label(&enemy))
+                .with_size(Size::Small)
+                .on_click(cx.listener(move |editor, _, _window, cx| {
+                    editor.edit_group(index, |g| g.enemy = next_enemy(&g.enemy));
+                    cx.notify();
+                })),
+        )
+        .child(stepper(
+            format!("group_{}_count", index),
+            format!("x{}", group.count),
+            cx.listener(move |editor, _, _window, cx| {
+                editor.edit_group(index, |g| g.count = g.count.saturating_sub(1).max(1));
+                cx.notify();
+            }),
+            cx.listener(move |editor, _, _window, cx| {
+                editor.edit_group(index, |g| g.count += 1);
+                cx.notify();
+            }),
+        ))
+        .child(stepper(
+            format!("group_{}_interval", index),
+            format!("Intervalle {:.1}s", group.interval),
+            cx.listener(move |editor, _, _window, cx| {
+                editor.edit_group(index, |g| g.interval = (g.interval - 0.1).max(0.0));
+                cx.notify();
+            }),
+            cx.listener(move |editor, _, _window, cx| {
+                editor.edit_group(index, |g| g.interval += 0.1);
+                cx.notify();
+            }),
+        ))
+        .child(stepper(
+            format!("group_{}_delay", index),
+            format!("Delai {:.1}s", group.delay),
+            cx.listener(move |editor, _, _window, cx| {
+                editor.edit_group(index, |g| g.delay = (g.delay - 0.5).max(0.0));
+                cx.notify();
+            }),
+            cx.listener(move |editor, _, _window, cx| {
+                editor.edit_group(index, |g| g.delay += 0.5);
+                cx.notify();
+            }),
+        ))
+        .child(
+            Button::new(SharedString::from(format!("group_{}_side", index)))
+                .label(side.name())
+                .with_size(Size::Small)
+                .on_click(cx.listener(move |editor, _, _window, cx| {
+                    editor.edit_group(index, |g| g.side = side.next());
+                    cx.notify();
+                })),
+        )
+        .child(
+            Button::new(SharedString::from(format!("group_{}_formation", index)))
+                .label(formation.name())
+                .with_size(Size::Small)
+                .on_click(cx.listener(move |editor, _, _window, cx| {
+                    editor.edit_group(index, |g| g.formation = formation.next());
+                    cx.notify();
+                })),
+        )
+        .child(
+            Button::new(SharedString::from(format!("group_{}_remove", index)))
+                .danger()
+                .label("X")
+                .with_size(Size::Small)
+                .on_click(cx.listener(move |editor, _, _window, cx| {
+                    if let Some(wave) = editor.wave_mut() {
+                        wave.groups.remove(index);
+                    }
+                    cx.notify();
+                })),
+        )
+}
+
+fn stepper(
+    id: String,
+    label: String,
+    on_decrease: impl Fn(&ClickEvent, &mut Window, &mut App) + 'static,
+    on_increase: impl Fn(&ClickEvent, &mut Window, &mut App) + 'static,
+) -> impl IntoElement {
+    h_flex()
+        .gap_1()
+        .items_center()
+        .child(
+            Button::new(SharedString::from(format!("{}_dec", id)))
+                .label("-")
+                .compact()
+                .with_size(Size::XSmall)
+                .on_click(on_decrease),
+        )
+        .child(div().text_xs().text_color(rgb(0xffffff)).child(label))
+        .child(
+            Button::new(SharedString::from(format!("{}_inc", id)))
+                .label("+")
+                .compact()
+                .with_size(Size::XSmall)
+                .on_click(on_increase),
+        )
+}
+
+fn pick_label(pick: &EnemyPick) -> String {
+    match pick {
+        EnemyPick::Id(id) => get_preset(id).map_or_else(|| id.clone(), |p| p.name.clone()),
+        EnemyPick::Mix(_) => "Melange".to_string(),
+    }
+}
+
+/// Following archetype in the editor's cycle; a mix becomes the first one
+fn next_enemy(pick: &EnemyPick) -> EnemyPick {
+    let presets = all_presets();
+    let next = match pick {
+        EnemyPick::Id(id) => presets
+            .iter()
+            .position(|p| &p.id == id)
+            .map_or(0, |i| (i + 1) % presets.len()),
+        EnemyPick::Mix(_) => 0,
+    };
+    EnemyPick::Id(presets[next].id.clone())
+}
+
+/// Average max HP and speed of an enemy of the pick at this wave
+fn expected_enemy(pick: &EnemyPick, wave_number: u32) -> (f32, f32) {
+    let stats = |id: &str| {
+        get_preset(id).map(|preset| {
+            let enemy = Enemy::new(0, preset, wave_number, Point2D::zero());
+            (enemy.max_hp, enemy.speed)
+        })
+    };
+    match pick {
+        EnemyPick::Id(id) => stats(id).unwrap_or((0.0, 1.0)),
+        EnemyPick::Mix(weights) => {
+            let total: f32 = weights.iter().map(|(_, w)| w).sum();
+            if total <= 0.0 {
+                return (0.0, 1.0);
+            }
+            weights
+                .iter()
+                .fold((0.0, 0.0), |(hp, speed), (id, weight)| {
+                    let (h, s) = stats(id).unwrap_or((0.0, 1.0));
+                    (hp + h * weight / total, speed + s * weight / total)
+                })
+        }
+    }
+}
+
+fn estimate_wave(wave: &WaveDef, wave_number: u32) -> WaveEstimate {
+    let mut estimate = WaveEstimate {
+        enemy_count: 0,
+        total_hp: 0.0,
+        duration: 0.0,
+        dps_needed: 0.0,
+    };
+    let mut last_arrival: f32 = 0.0;
+    for group in &wave.groups {
+        if group.count == 0 {
+            continue;
+        }
+        let (hp, speed) = expected_enemy(&group.enemy, wave_number);
+        let last_spawn = group.delay + group.interval * (group.count - 1) as f32;
+        estimate.enemy_count += group.count;
+        estimate.total_hp += hp * group.count as f32;
+        estimate.duration = estimate.duration.max(last_spawn);
+        last_arrival = last_arrival.max(last_spawn + ESTIMATE_TRAVEL_DISTANCE / speed.max(1.0));
+    }
+    if last_arrival > 0.0 {
+        estimate.dps_needed = estimate.total_hp / last_arrival;
+    }
+    estimate
+}
@@ -1,18 +1,93 @@
 use gpui::*;
-use gpui_component::v_flex;
-use std::time::Duration;
+use gpui_component::button::{Button, ButtonVariant, ButtonVariants};
+use gpui_component::dialog::DialogButtonProps;
+use gpui_component::input::{Input, InputState};
+use gpui_component::{Disableable, Sizable, Size, WindowExt, h_flex, v_flex};
 
-use crate::app::{Screen, SentinelsApp};
+use crate::app::SentinelsApp;
+use crate::data::profile::{ProfileIndex, ProfileSummary};
 
-pub fn render(cx: &mut Context<SentinelsApp>) -> impl IntoElement {
-    cx.spawn(async |this: WeakEntity<SentinelsApp>, cx| {
-        Timer::after(Duration::from_secs(2)).await;
-        this.update(cx, |app, cx| {
-            app.navigate_to(Screen::Lobby, cx);
-        })
-        .ok();
-    })
-    .detach();
+/// Profile picker shown at launch
+pub fn render(
+    profiles: &ProfileIndex,
+    summaries: &[ProfileSummary],
+    cx: &mut Context<SentinelsApp>,
+) -> impl IntoElement {
+    let can_delete = profiles.profiles.len() > 1;
+    let current = profiles.current().id;
+
+    let rows = profiles.profiles.iter().map(|profile| {
+        let id = profile.id;
+        let name = profile.name.clone();
+        let summary = summaries.iter().find(|s| s.id == id);
+        h_flex()
+            .items_center()
+            .justify_between()
+            .gap_4()
+            .p_3()
+            .rounded_md()
+            .bg(rgb(0x2a2a4a))
+            .child(
+                v_flex()
+                    .gap_1()
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(rgb(if id == current { 0xffd700 } else { 0xffffff }))
+                            .child(profile.name.clone()),
+                    )
+                    .children(summary.map(|save| {
+                        div().text_xs().text_color(rgb(0x888888)).child(format!(
+                            "Meilleur score: {} | Vague max: {} | Pepites: {}",
+                            save.best_score, save.max_wave, save.pepites
+                        ))
+                    })),
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(
+                        Button::new(SharedString::from(format!("profile_play_{}", id)))
+                            .primary()
+                            .label("Jouer")
+                            .with_size(Size::Small)
+                            .on_click(cx.listener(move |app, _, _window, cx| {
+                                app.select_profile(id, cx);
+                            })),
+                    )
+                    .child(
+                        Button::new(SharedString::from(format!("profile_rename_{}", id)))
+                            .ghost()
+                            .label("Renommer")
+                            .with_size(Size::Small)
+                            .on_click({
+                                let name = name.clone();
+                                cx.listener(move |_app, _, window, cx| {
+                                    open_name_dialog(Some(id), name.clone(), window, cx);
+                                })
+                            }),
+                    )
+                    .child(
+                        Button::new(SharedString::from(format!("profile_duplicate_{}", id)))
+                            .ghost()
+                            .label("Dupliquer")
+                            .with_size(Size::Small)
+                            .on_click(cx.listener(move |app, _, _window, cx| {
+                                app.duplicate_profile(id, cx);
+                            })),
+                    )
+                    .child(
+                        Button::new(SharedString::from(format!("profile_delete_{}", id)))
+                            .danger()
+                            .label("Supprimer")
+                            .with_size(Size::Small)
+                            .disabled(!can_delete)
+                            .on_click(cx.listener(move |_app, _, window, cx| {
+                                confirm_delete(id, name.clone(), window, cx);
+                            })),
+                    ),
+            )
+    });
 
     v_flex()
         .size_full()
@@ -24,6 +99,87 @@ pub fn render(cx: &mut Context<SentinelsApp>) -> impl IntoElement {
             div()
                 .text_sm()
                 .text_color(rgb(0x888888))
-                .child("Chargement..."),
+                .child("Choisissez un profil"),
+        )
+        .child(v_flex().gap_2().w(px(560.)).children(rows))
+        .child(
+            Button::new("profile_new")
+                .ghost()
+                .label("Nouveau profil")
+                .on_click(cx.listener(|app, _, window, cx| {
+                    let name = app.profiles.unused_name("Profil");
+                    open_name_dialog(None, name, window, cx);
+                })),
         )
 }
+
+/// Asks for a profile name, to rename `profile` or to create a new one when
+/// it is None. Refused names keep the dialog open.
+fn open_name_dialog(
+    profile: Option<u32>,
+    name: String,
+    window: &mut Window,
+    cx: &mut Context<SentinelsApp>,
+) {
+    let input = cx.new(|cx| InputState::new(window, cx).default_value(name));
+    input.update(cx, |state, cx| state.focus(window, cx));
+    let app = cx.weak_entity();
+    let title = if profile.is_some() {
+        "Renommer le profil"
+    } else {
+        "Nouveau profil"
+    };
+    window.open_dialog(cx, move |dialog, _window, _cx| {
+        let input_state = input.clone();
+        let app = app.clone();
+        dialog
+            .title(title)
+            .child(
+                v_flex().gap_2().child(Input::new(&input)).child(
+                    div()
+                        .text_xs()
+                        .text_color(rgb(0x888888))
+                        .child("Le nom doit etre unique"),
+                ),
+            )
+            .confirm()
+            .button_props(
+                DialogButtonProps::default()
+                    .ok_text("Valider")
+                    .cancel_text("Annuler"),
+            )
+            .on_ok(move |_, _window, cx| {
+                let name = input_state.read(cx).value();
+                app.update(cx, |app, cx| match profile {
+                    Some(id) => app.rename_profile(id, &name, cx),
+                    None => app.create_profile(&name, cx),
+                })
+                .unwrap_or(true)
+            })
+    });
+}
+
+/// Deletes the profile `id` and its files once confirmed
+fn confirm_delete(id: u32, name: String, window: &mut Window, cx: &mut Context<SentinelsApp>) {
+    let app = cx.weak_entity();
+    window.open_dialog(cx, move |dialog, _window, _cx| {
+        let app = app.clone();
+        dialog
+            .title("Supprimer le profil")
+            .child(format!(
+                "Le profil \"{}\", sa sauvegarde et son historique seront effaces.",
+                name
+            ))
+            .confirm()
+            .button_props(
+                DialogButtonProps::default()
+                    .ok_text("Supprimer")
+                    .ok_variant(ButtonVariant::Danger)
+                    .cancel_text("Annuler"),
+            )
+            .on_ok(move |_, _window, cx| {
+                app.update(cx, |app, cx| app.delete_profile(id, cx)).ok();
+                true
+            })
+    });
+}
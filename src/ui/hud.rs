@@ -6,8 +6,16 @@ use gpui_component::divider::Divider;
 use gpui_component::progress::Progress;
 use gpui_component::{Disableable, Sizable, Size, h_flex, v_flex};
 
+use crate::data::blueprint::Blueprint;
+use crate::data::enemy_types::EnemyVisual;
 use crate::data::tower_defs::{TowerKind, get_def};
-use crate::game::{GamePhase, GameState};
+use crate::game::enemy::Enemy;
+use crate::game::event::{GameEvent, GameEventKind};
+use crate::game::stats::{DamageStats, RunStats};
+use crate::game::tower::{TargetingMode, Tower, UpgradePolicy};
+use crate::game::wave::WavePreview;
+use crate::game::{DAMAGE_NUMBER_DURATION, GOLD_NUMBER_DURATION, GamePhase, GameState};
+use crate::render::shapes::draw_polygon;
 use crate::screens::play::PlayScreen;
 
 const SIDEBAR_WIDTH: f32 = 200.0;
@@ -16,7 +24,11 @@ pub fn sidebar_width() -> f32 {
     SIDEBAR_WIDTH
 }
 
-pub fn render_sidebar(game: &GameState, cx: &mut Context<PlayScreen>) -> impl IntoElement + use<> {
+pub fn render_sidebar(
+    game: &GameState,
+    blueprints: &[Blueprint],
+    cx: &mut Context<PlayScreen>,
+) -> impl IntoElement + use<> {
     let hp = game.player.hp;
     let max_hp = game.player.max_hp;
     let gold = game.economy.gold;
@@ -24,12 +36,23 @@ pub fn render_sidebar(game: &GameState, cx: &mut Context<PlayScreen>) -> impl In
     let wave = game.economy.wave_number;
     let score = game.economy.score;
     let phase = game.phase;
+    let time_to_wave = game.wave_manager.time_to_next_wave();
+    let early_bonus = game.wave_manager.early_call_bonus();
+    let auto_start = game.wave_manager.auto_start;
     let tower_count = game.towers.len();
     let max_towers = game.max_towers;
     let player_gold = game.economy.gold;
     let shield = game.shield.clone();
+    let undo_label = game
+        .history
+        .last()
+        .filter(|_| phase == GamePhase::Preparing)
+        .map(|action| format!("Annuler {} (Ctrl+Z)", action.label()));
 
     let selected_section = selected_tower_section(game, cx);
+    let multi_section = multi_selection_section(game, cx);
+    let blueprint_section = blueprint_section(game, blueprints, cx);
+    let preview_section = game.wave_manager.preview().map(next_wave_section);
 
     v_flex()
         .w(px(SIDEBAR_WIDTH))
@@ -54,19 +77,75 @@ pub fn render_sidebar(game: &GameState, cx: &mut Context<PlayScreen>) -> impl In
         .child(stats_section(
             hp, max_hp, gold, pepites, wave, score, &shield,
         ))
+        // Player stats after shop upgrades
+        .child(player_stats_section(game))
+        // Upcoming wave composition, between waves
+        .when_some(preview_section, |this, section| this.child(section))
         // Tower grid section
-        .child(tower_grid_section(player_gold, tower_count, max_towers, cx))
+        .child(tower_grid_section(
+            game,
+            player_gold,
+            tower_count,
+            max_towers,
+            cx,
+        ))
+        // Blueprint section
+        .child(blueprint_section)
         // Selected tower section (scrollable)
         .when_some(selected_section, |this, section| this.child(section))
+        // Multi-selection section (bulk operations)
+        .when_some(multi_section, |this, section| this.child(section))
         // Bottom: wave button or game over
         .child(
             v_flex()
                 .flex_shrink_0()
+                .gap_2()
+                .when_some(undo_label, |this, label| {
+                    this.child(
+                        Button::new("undo_action")
+                            .label(label)
+                            .compact()
+                            .with_size(Size::Small)
+                            .on_click(cx.listener(|screen, _, _window, _cx| {
+                                screen.game_state.undo_last_action();
+                            })),
+                    )
+                })
                 .when(phase == GamePhase::Preparing, |this| {
                     this.child(
+                        h_flex()
+                            .items_center()
+                            .justify_between()
+                            .child(div().text_xs().text_color(rgb(0xaaaaaa)).child(
+                                if auto_start || time_to_wave > 0.0 {
+                                    format!("Prochaine vague: {:.1}s", time_to_wave)
+                                } else {
+                                    "En attente".to_string()
+                                },
+                            ))
+                            .child(
+                                h_flex()
+                                    .gap_1()
+                                    .items_center()
+                                    .child(
+                                        Checkbox::new("auto_start").checked(auto_start).on_click(
+                                            cx.listener(|screen, _, _window, _cx| {
+                                                let waves = &mut screen.game_state.wave_manager;
+                                                waves.auto_start = !waves.auto_start;
+                                            }),
+                                        ),
+                                    )
+                                    .child(div().text_xs().text_color(rgb(0xffffff)).child("Auto")),
+                            ),
+                    )
+                    .child(
                         Button::new("start_wave")
                             .primary()
-                            .label("Lancer la vague")
+                            .label(if early_bonus > 0 {
+                                format!("Lancer la vague (+{} or)", early_bonus)
+                            } else {
+                                "Lancer la vague".to_string()
+                            })
                             .on_click(cx.listener(|screen, _, _window, _cx| {
                                 screen.game_state.start_wave();
                             })),
@@ -151,7 +230,111 @@ fn stats_section(
         .child(stat_row("Score", format!("{}", score), rgb(0xffffff)))
 }
 
+/// Effective player stats and economy bonuses, shop upgrades included
+fn player_stats_section(game: &GameState) -> impl IntoElement + use<> {
+    let player = &game.player;
+    v_flex()
+        .gap_1()
+        .child(stat_row(
+            "Degats",
+            format!("{:.1}", player.attack_damage),
+            rgb(0xff8866),
+        ))
+        .child(stat_row(
+            "Portee",
+            format!("{:.0}", player.attack_range),
+            rgb(0xffffff),
+        ))
+        .child(stat_row(
+            "Gain d'or",
+            format!("x{:.2}", game.modifiers.gold_earn_multiplier),
+            rgb(0xffd700),
+        ))
+        .child(stat_row(
+            "Interets",
+            format!(
+                "{:.0}% (max {})",
+                game.modifiers.interest_rate * 100.0,
+                game.modifiers.interest_cap
+            ),
+            rgb(0xffd700),
+        ))
+}
+
+fn next_wave_section(preview: &WavePreview) -> impl IntoElement + use<> {
+    let rows: Vec<AnyElement> = preview
+        .enemies
+        .iter()
+        .map(|(preset, count)| {
+            h_flex()
+                .items_center()
+                .gap_2()
+                .child(enemy_icon(preset.visual))
+                .child(
+                    div()
+                        .flex_1()
+                        .text_xs()
+                        .text_color(rgb(0xcccccc))
+                        .child(preset.name.clone()),
+                )
+                .child(
+                    div()
+                        .text_xs()
+                        .text_color(rgb(0xffffff))
+                        .child(format!("x{}", count)),
+                )
+                .into_any_element()
+        })
+        .collect();
+
+    v_flex()
+        .gap_1()
+        .child(Divider::horizontal().color(Hsla {
+            h: 0.0,
+            s: 0.0,
+            l: 0.25,
+            a: 1.0,
+        }))
+        .child(
+            h_flex()
+                .items_center()
+                .justify_between()
+                .child(
+                    div()
+                        .text_xs()
+                        .text_color(rgb(0xaaaaaa))
+                        .child(format!("Vague {}", preview.wave_number)),
+                )
+                .when(preview.has_boss, |this| {
+                    this.child(div().text_xs().text_color(rgb(0xff4444)).child("BOSS"))
+                }),
+        )
+        .children(rows)
+        .child(stat_row(
+            "PV totaux",
+            format!("{:.0}", preview.total_hp),
+            rgb(0xff8866),
+        ))
+}
+
+/// Enemy body as drawn on the map, shrunk to an icon
+fn enemy_icon(visual: EnemyVisual) -> impl IntoElement {
+    canvas(
+        |_bounds, _window, _cx| {},
+        move |bounds, _, window, _cx| {
+            let center = point(
+                bounds.origin.x + bounds.size.width / 2.0,
+                bounds.origin.y + bounds.size.height / 2.0,
+            );
+            draw_polygon(window, center, 6.0, visual.sides, visual.color(), 0.0);
+        },
+    )
+    .size(px(14.0))
+    .flex_shrink_0()
+}
+
 fn tower_grid_section(
+    game: &GameState,
     gold: u32,
     tower_count: usize,
     max_towers: u32,
@@ -160,7 +343,10 @@ fn tower_grid_section(
     let slots_full = tower_count >= max_towers as usize;
 
     let mut tower_icons: Vec<AnyElement> = Vec::new();
-    for kind in TowerKind::all() {
+    for kind in TowerKind::all()
+        .iter()
+        .filter(|k| game.is_tower_available(**k))
+    {
         tower_icons.push(tower_icon(*kind, gold, slots_full, cx).into_any_element());
     }
 
@@ -191,18 +377,151 @@ fn tower_grid_section(
         .child(div().flex().flex_wrap().gap_2().children(tower_icons))
 }
 
+fn blueprint_section(
+    game: &GameState,
+    blueprints: &[Blueprint],
+    cx: &mut Context<PlayScreen>,
+) -> impl IntoElement + use<> {
+    let has_towers = !game.towers.is_empty();
+
+    let active = game.blueprint.as_ref().map(|blueprint| {
+        let total = blueprint.ghosts.len();
+        let remaining = game.unbuilt_blueprint_towers().len();
+        let auto_build = blueprint.auto_build;
+
+        v_flex()
+            .gap_1()
+            .child(
+                h_flex()
+                    .items_center()
+                    .justify_between()
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(rgb(0xffffff))
+                            .child(blueprint.name.clone()),
+                    )
+                    .child(div().text_xs().text_color(rgb(0x888888)).child(format!(
+                        "{}/{}",
+                        total - remaining,
+                        total
+                    ))),
+            )
+            .child(
+                h_flex()
+                    .items_center()
+                    .justify_between()
+                    .child(
+                        h_flex()
+                            .items_center()
+                            .gap_2()
+                            .child(
+                                Checkbox::new("blueprint_auto")
+                                    .checked(auto_build)
+                                    .on_click(cx.listener(|screen, _, _window, _cx| {
+                                        if let Some(blueprint) = &mut screen.game_state.blueprint {
+                                            blueprint.auto_build = !blueprint.auto_build;
+                                        }
+                                    })),
+                            )
+                            .child(div().text_xs().text_color(rgb(0xffffff)).child("Auto")),
+                    )
+                    .child(
+                        Button::new("blueprint_clear")
+                            .label("Retirer")
+                            .compact()
+                            .with_size(Size::XSmall)
+                            .on_click(cx.listener(|screen, _, _window, _cx| {
+                                screen.game_state.blueprint = None;
+                            })),
+                    ),
+            )
+    });
+
+    let saved_buttons: Vec<AnyElement> = blueprints
+        .iter()
+        .enumerate()
+        .map(|(i, blueprint)| {
+            Button::new(SharedString::from(format!("blueprint_load_{}", i)))
+                .label(blueprint.name.clone())
+                .compact()
+                .with_size(Size::XSmall)
+                .on_click(cx.listener(move |screen, _, _window, _cx| {
+                    if let Some(blueprint) = screen.blueprints.get(i).cloned() {
+                        screen.game_state.load_blueprint(&blueprint);
+                    }
+                }))
+                .into_any_element()
+        })
+        .collect();
+
+    v_flex()
+        .gap_2()
+        .child(Divider::horizontal().color(Hsla {
+            h: 0.0,
+            s: 0.0,
+            l: 0.25,
+            a: 1.0,
+        }))
+        .child(div().text_xs().text_color(rgb(0xaaaaaa)).child("Plans"))
+        .when_some(active, |this, section| this.child(section))
+        .child(
+            h_flex()
+                .gap_1()
+                .child(
+                    Button::new("blueprint_save")
+                        .label("Sauver")
+                        .compact()
+                        .with_size(Size::XSmall)
+                        .disabled(!has_towers)
+                        .on_click(cx.listener(|screen, _, _window, _cx| {
+                            screen.save_blueprint();
+                        })),
+                )
+                .child(
+                    Button::new("blueprint_export")
+                        .label("Exporter")
+                        .compact()
+                        .with_size(Size::XSmall)
+                        .disabled(!has_towers)
+                        .on_click(cx.listener(|screen, _, _window, cx| {
+                            let code =
+                                Blueprint::from_towers(String::new(), &screen.game_state.towers)
+                                    .to_code();
+                            cx.write_to_clipboard(ClipboardItem::new_string(code));
+                        })),
+                )
+                .child(
+                    Button::new("blueprint_import")
+                        .label("Importer")
+                        .compact()
+                        .with_size(Size::XSmall)
+                        .on_click(cx.listener(|screen, _, _window, cx| {
+                            screen.import_blueprint(cx);
+                        })),
+                ),
+        )
+        .child(div().flex().flex_wrap().gap_1().children(saved_buttons))
+}
+
 fn selected_tower_section(
     game: &GameState,
     cx: &mut Context<PlayScreen>,
 ) -> Option<impl IntoElement + use<>> {
-    let tower_idx = game.selected_tower?;
+    let tower_idx = game.single_selected()?;
     let tower = game.towers.get(tower_idx)?;
 
     let color = tower.element.color();
     let name = tower.name;
-    let sell_value = tower.sell_value();
+    let sell_value = game.sell_value(tower_idx);
+    let invested = tower.invested;
+    let targeting = (tower.attack_range() > 0.0).then_some(tower.targeting);
+    let refund_ratio = game.refund_ratio;
     let gold = game.economy.gold;
     let has_notification_settings = tower.notification_settings.is_some();
+    let hp = tower.hp;
+    let max_hp = tower.max_hp;
+    let repair_cost = tower.repair_cost();
 
     // Build stat rows: each stat shows its current value, and if upgradeable, a button with bonus
     // Skip stats for towers with notification settings (Alarme) as they don't attack
@@ -258,7 +577,7 @@ fn selected_tower_section(
                             .with_size(Size::XSmall)
                             .disabled(!can_afford)
                             .on_click(cx.listener(move |screen, _, _window, _cx| {
-                                if let Some(idx) = screen.game_state.selected_tower {
+                                if let Some(idx) = screen.game_state.single_selected() {
                                     screen.game_state.upgrade_tower(idx, uid);
                                 }
                             })),
@@ -277,18 +596,31 @@ fn selected_tower_section(
         .with_size(Size::Small)
         .disabled(!can_move)
         .on_click(cx.listener(move |screen, _, _window, _cx| {
-            if let Some(idx) = screen.game_state.selected_tower {
+            if let Some(idx) = screen.game_state.single_selected() {
                 screen.game_state.move_mode = Some(idx);
             }
         }));
 
+    let repair_btn = tower.is_damaged().then(|| {
+        Button::new("sidebar_repair_tower")
+            .label(format!("Reparer ({}g)", repair_cost))
+            .compact()
+            .with_size(Size::Small)
+            .disabled(gold < repair_cost)
+            .on_click(cx.listener(move |screen, _, _window, _cx| {
+                if let Some(idx) = screen.game_state.single_selected() {
+                    screen.game_state.repair_tower(idx);
+                }
+            }))
+    });
+
     let sell_btn = Button::new("sidebar_sell_tower")
         .danger()
-        .label(format!("Vendre ({}g)", sell_value))
+        .label(format!("Vendre (+{}g)", sell_value))
         .compact()
         .with_size(Size::Small)
         .on_click(cx.listener(move |screen, _, _window, _cx| {
-            if let Some(idx) = screen.game_state.selected_tower {
+            if let Some(idx) = screen.game_state.single_selected() {
                 screen.game_state.sell_tower(idx);
             }
         }));
@@ -297,6 +629,7 @@ fn selected_tower_section(
     let notification_section = tower.notification_settings.as_ref().map(|settings| {
         let shield_broken = settings.shield_broken;
         let shield_low = settings.shield_low;
+        let tower_destroyed = settings.tower_destroyed;
 
         v_flex()
             .gap_2()
@@ -320,7 +653,7 @@ fn selected_tower_section(
                         Checkbox::new("notif_shield_broken")
                             .checked(shield_broken)
                             .on_click(cx.listener(move |screen, _, _window, _cx| {
-                                if let Some(idx) = screen.game_state.selected_tower {
+                                if let Some(idx) = screen.game_state.single_selected() {
                                     if let Some(tower) = screen.game_state.towers.get_mut(idx) {
                                         if let Some(settings) = &mut tower.notification_settings {
                                             settings.shield_broken = !settings.shield_broken;
@@ -344,7 +677,7 @@ fn selected_tower_section(
                         Checkbox::new("notif_shield_low")
                             .checked(shield_low)
                             .on_click(cx.listener(move |screen, _, _window, _cx| {
-                                if let Some(idx) = screen.game_state.selected_tower {
+                                if let Some(idx) = screen.game_state.single_selected() {
                                     if let Some(tower) = screen.game_state.towers.get_mut(idx) {
                                         if let Some(settings) = &mut tower.notification_settings {
                                             settings.shield_low = !settings.shield_low;
@@ -360,6 +693,32 @@ fn selected_tower_section(
                             .child("Bouclier <= 25%"),
                     ),
             )
+            .child(
+                h_flex()
+                    .items_center()
+                    .gap_2()
+                    .child(
+                        Checkbox::new("notif_tower_destroyed")
+                            .checked(tower_destroyed)
+                            .on_click(cx.listener(move |screen, _, _window, _cx| {
+                                let tower = screen
+                                    .game_state
+                                    .single_selected()
+                                    .and_then(|idx| screen.game_state.towers.get_mut(idx));
+                                if let Some(settings) =
+                                    tower.and_then(|t| t.notification_settings.as_mut())
+                                {
+                                    settings.tower_destroyed = !settings.tower_destroyed;
+                                }
+                            })),
+                    )
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(rgb(0xffffff))
+                            .child("Tour detruite"),
+                    ),
+            )
     });
 
     Some(
@@ -379,17 +738,274 @@ fn selected_tower_section(
             }))
             // Header
             .child(div().text_sm().text_color(color).child(name))
+            // Gold invested and exact refund
+            .child(div().text_xs().text_color(rgb(0x888888)).child(format!(
+                "Investi: {}g | Revente: {}g ({:.0}%)",
+                invested,
+                sell_value,
+                refund_ratio * 100.0
+            )))
+            // Tower HP
+            .child(stat_row(
+                "PV",
+                format!("{:.0}/{:.0}", hp.max(0.0), max_hp),
+                rgb(0x44ff44),
+            ))
             // Stats with inline upgrades
             .children(stat_elements)
+            // Targeting mode (attacking towers only)
+            .when_some(targeting, |this, mode| {
+                this.child(targeting_row(Some(mode), cx))
+            })
+            // Auto-upgrade policy (attacking towers only)
+            .when(!upgrades.is_empty(), |this| {
+                this.child(upgrade_policy_section(tower, cx))
+            })
             // Notification settings (if available)
             .when_some(notification_section, |this, section| this.child(section))
             // Move
             .child(move_btn)
+            // Repair (damaged towers only)
+            .when_some(repair_btn, |this, btn| this.child(btn))
             // Sell
             .child(sell_btn),
     )
 }
 
+/// Applies `f` to the policy of the single selected tower, if it has one
+fn edit_selected_policy(screen: &mut PlayScreen, f: impl FnOnce(&mut UpgradePolicy)) {
+    let Some(idx) = screen.game_state.single_selected() else {
+        return;
+    };
+    if let Some(policy) = screen
+        .game_state
+        .towers
+        .get_mut(idx)
+        .and_then(|tower| tower.upgrade_policy.as_mut())
+    {
+        f(policy);
+    }
+}
+
+/// Auto-upgrade toggle, priority order and gold reserve for the selected tower
+fn upgrade_policy_section(tower: &Tower, cx: &mut Context<PlayScreen>) -> impl IntoElement + use<> {
+    let policy = tower.upgrade_policy.clone();
+    let enabled = policy.is_some();
+
+    let toggle = h_flex()
+        .items_center()
+        .gap_2()
+        .child(
+            Checkbox::new("auto_upgrade_toggle")
+                .checked(enabled)
+                .on_click(cx.listener(move |screen, _, _window, _cx| {
+                    let tower = screen
+                        .game_state
+                        .single_selected()
+                        .and_then(|idx| screen.game_state.towers.get_mut(idx));
+                    if let Some(tower) = tower {
+                        tower.upgrade_policy = match tower.upgrade_policy {
+                            Some(_) => None,
+                            None => Some(tower.default_upgrade_policy()),
+                        };
+                    }
+                })),
+        )
+        .child(
+            div()
+                .text_xs()
+                .text_color(rgb(0xffffff))
+                .child("Amelioration auto"),
+        );
+
+    let details = policy.map(|policy| {
+        let priorities: Vec<AnyElement> = policy
+            .priorities
+            .iter()
+            .enumerate()
+            .map(|(i, name)| {
+                h_flex()
+                    .items_center()
+                    .justify_between()
+                    .child(div().text_xs().text_color(rgb(0xcccccc)).child(format!(
+                        "{}. {}",
+                        i + 1,
+                        name
+                    )))
+                    .child(
+                        Button::new(SharedString::from(format!("policy_up_{}", i)))
+                            .label("^")
+                            .compact()
+                            .with_size(Size::XSmall)
+                            .disabled(i == 0)
+                            .on_click(cx.listener(move |screen, _, _window, _cx| {
+                                edit_selected_policy(screen, |policy| policy.move_up(i));
+                            })),
+                    )
+                    .into_any_element()
+            })
+            .collect();
+
+        v_flex().gap_1().children(priorities).child(
+            h_flex()
+                .items_center()
+                .justify_between()
+                .child(
+                    div()
+                        .text_xs()
+                        .text_color(rgb(0xffd700))
+                        .child(format!("Reserve: {}g", policy.gold_reserve)),
+                )
+                .child(
+                    h_flex()
+                        .gap_1()
+                        .child(
+                            Button::new("policy_reserve_down")
+                                .label(format!("-{}", UpgradePolicy::RESERVE_STEP))
+                                .compact()
+                                .with_size(Size::XSmall)
+                                .disabled(policy.gold_reserve == 0)
+                                .on_click(cx.listener(|screen, _, _window, _cx| {
+                                    edit_selected_policy(screen, UpgradePolicy::decrease_reserve);
+                                })),
+                        )
+                        .child(
+                            Button::new("policy_reserve_up")
+                                .label(format!("+{}", UpgradePolicy::RESERVE_STEP))
+                                .compact()
+                                .with_size(Size::XSmall)
+                                .on_click(cx.listener(|screen, _, _window, _cx| {
+                                    edit_selected_policy(screen, UpgradePolicy::increase_reserve);
+                                })),
+                        ),
+                ),
+        )
+    });
+
+    v_flex()
+        .gap_1()
+        .child(toggle)
+        .when_some(details, |this, details| this.child(details))
+}
+
+/// Targeting mode buttons applied to every selected tower; `current` is
+/// None when the selection has mixed modes
+fn targeting_row(
+    current: Option<TargetingMode>,
+    cx: &mut Context<PlayScreen>,
+) -> impl IntoElement + use<> {
+    let buttons: Vec<AnyElement> = TargetingMode::all()
+        .iter()
+        .map(|mode| {
+            let mode = *mode;
+            let button = Button::new(SharedString::from(format!("targeting_{:?}", mode)))
+                .label(mode.name())
+                .compact()
+                .with_size(Size::XSmall)
+                .on_click(cx.listener(move |screen, _, _window, _cx| {
+                    screen.game_state.set_selection_targeting(mode);
+                }));
+            if current == Some(mode) {
+                button.primary().into_any_element()
+            } else {
+                button.into_any_element()
+            }
+        })
+        .collect();
+
+    v_flex()
+        .gap_1()
+        .child(div().text_xs().text_color(rgb(0xaaaaaa)).child("Ciblage"))
+        .child(div().flex().flex_wrap().gap_1().children(buttons))
+}
+
+fn multi_selection_section(
+    game: &GameState,
+    cx: &mut Context<PlayScreen>,
+) -> Option<impl IntoElement + use<>> {
+    let indices = game.selected_indices();
+    if indices.len() < 2 {
+        return None;
+    }
+
+    let gold = game.economy.gold;
+    let total_refund: u32 = indices.iter().map(|idx| game.sell_value(*idx)).sum();
+    let first_mode = game.towers[indices[0]].targeting;
+    let shared_mode = indices
+        .iter()
+        .all(|idx| game.towers[*idx].targeting == first_mode)
+        .then_some(first_mode);
+
+    let upgrade_rows: Vec<AnyElement> = game
+        .common_selection_upgrades()
+        .into_iter()
+        .enumerate()
+        .map(|(i, upgrade)| {
+            let id = upgrade.id;
+            let name = upgrade.name;
+            let mut row = h_flex()
+                .items_center()
+                .justify_between()
+                .child(div().text_xs().text_color(rgb(0xcccccc)).child(format!(
+                    "{} ({}/{})",
+                    name,
+                    upgrade.upgradable,
+                    indices.len()
+                )));
+            if upgrade.upgradable > 0 {
+                row = row.child(
+                    Button::new(SharedString::from(format!("btn_upgrade_all_{}", i)))
+                        .label(format!("Tout {}g", upgrade.total_cost))
+                        .compact()
+                        .with_size(Size::XSmall)
+                        .disabled(gold == 0)
+                        .tooltip("Ameliore les tours selectionnees, les moins cheres d'abord, dans la limite de l'or disponible")
+                        .on_click(cx.listener(move |screen, _, _window, _cx| {
+                            let budget = screen.game_state.economy.gold;
+                            screen.game_state.upgrade_selection(id, name, budget);
+                        })),
+                );
+            }
+            row.into_any_element()
+        })
+        .collect();
+
+    let sell_all_btn = Button::new("sidebar_sell_selection")
+        .danger()
+        .label(format!("Tout vendre (+{}g)", total_refund))
+        .compact()
+        .with_size(Size::Small)
+        .on_click(cx.listener(|screen, _, _window, _cx| {
+            screen.game_state.sell_selection();
+        }));
+
+    Some(
+        div()
+            .id("multi_selection_panel")
+            .flex_1()
+            .overflow_y_scroll()
+            .flex()
+            .flex_col()
+            .gap_2()
+            .pt_2()
+            .child(Divider::horizontal().color(Hsla {
+                h: 0.0,
+                s: 0.0,
+                l: 0.25,
+                a: 1.0,
+            }))
+            .child(
+                div()
+                    .text_sm()
+                    .text_color(rgb(0xffffff))
+                    .child(format!("{} tours selectionnees", indices.len())),
+            )
+            .children(upgrade_rows)
+            .child(targeting_row(shared_mode, cx))
+            .child(sell_all_btn),
+    )
+}
+
 fn tower_icon(
     kind: TowerKind,
     gold: u32,
@@ -445,3 +1061,504 @@ fn tower_icon(
             }
         }))
 }
+
+/// Recent game events, top-left of the canvas, fading out as they expire
+pub fn render_event_feed(events: &[GameEvent]) -> impl IntoElement {
+    let rows: Vec<AnyElement> = events
+        .iter()
+        .map(|event| {
+            div()
+                .text_xs()
+                .text_color(Hsla {
+                    h: 0.0,
+                    s: 0.8,
+                    l: 0.6,
+                    a: event.lifetime.min(1.0),
+                })
+                .child(event.message())
+                .into_any_element()
+        })
+        .collect();
+
+    v_flex()
+        .id("event_feed")
+        .absolute()
+        .top_3()
+        .left_3()
+        .gap_1()
+        .children(rows)
+}
+
+/// Floating damage numbers over the canvas, rising as they fade. Hits reduced
+/// by armor show the damage dealt in blue followed by the raw damage. Gold
+/// earned outside kills rises in gold the same way.
+pub fn render_damage_numbers(game: &GameState) -> impl IntoElement + use<> {
+    let canvas_center_x = (game.viewport_size.0 - sidebar_width()) / 2.0;
+    let canvas_center_y = game.viewport_size.1 / 2.0;
+
+    let numbers: Vec<AnyElement> = game
+        .damage_numbers
+        .iter()
+        .map(|number| {
+            let progress = 1.0 - number.lifetime / DAMAGE_NUMBER_DURATION;
+            let alpha = number.lifetime / DAMAGE_NUMBER_DURATION;
+            let mitigated = number.raw - number.amount >= 0.5;
+            let amount_color = if mitigated {
+                Hsla {
+                    h: 0.58,
+                    s: 0.7,
+                    l: 0.75,
+                    a: alpha,
+                }
+            } else {
+                Hsla {
+                    h: 0.0,
+                    s: 0.0,
+                    l: 1.0,
+                    a: alpha,
+                }
+            };
+
+            h_flex()
+                .absolute()
+                .left(px(canvas_center_x + number.position.x - 12.0))
+                .top(px(canvas_center_y + number.position.y
+                    - 30.0
+                    - 20.0 * progress))
+                .gap_0p5()
+                .child(
+                    div()
+                        .text_xs()
+                        .text_color(amount_color)
+                        .child(format!("{:.0}", number.amount)),
+                )
+                .when(mitigated, |this| {
+                    this.child(
+                        div()
+                            .text_xs()
+                            .text_color(Hsla {
+                                h: 0.0,
+                                s: 0.0,
+                                l: 0.55,
+                                a: alpha,
+                            })
+                            .child(format!("/{:.0}", number.raw)),
+                    )
+                })
+                .into_any_element()
+        })
+        .collect();
+
+    let gold_numbers = game.gold_numbers.iter().map(|number| {
+        let progress = 1.0 - number.lifetime / GOLD_NUMBER_DURATION;
+        let alpha = number.lifetime / GOLD_NUMBER_DURATION;
+        div()
+            .absolute()
+            .left(px(canvas_center_x + number.position.x - 12.0))
+            .top(px(canvas_center_y + number.position.y
+                - 40.0
+                - 30.0 * progress))
+            .text_sm()
+            .text_color(Hsla {
+                a: alpha,
+                ..rgb(0xffd700).into()
+            })
+            .child(format!("+{}g", number.amount))
+            .into_any_element()
+    });
+
+    div()
+        .id("damage_numbers")
+        .absolute()
+        .top_0()
+        .left_0()
+        .size_full()
+        .children(numbers)
+        .children(gold_numbers)
+}
+
+/// One HP bar per living boss across the top of the canvas, with phase
+/// thresholds marked on the bar
+pub fn render_boss_bars(enemies: &[Enemy]) -> Option<impl IntoElement + use<>> {
+    let bars: Vec<AnyElement> = enemies
+        .iter()
+        .filter_map(|enemy| {
+            let boss = enemy.boss.as_ref()?;
+            let ratio = (enemy.hp / enemy.max_hp).clamp(0.0, 1.0);
+            let phase = boss.active_phase().map_or("", |p| p.name);
+            let immunity = boss
+                .immunity
+                .map(|(element, _)| format!(" | Immunise: {}", element.name()))
+                .unwrap_or_default();
+            let markers: Vec<AnyElement> = boss
+                .def
+                .phases
+                .iter()
+                .map(|p| {
+                    div()
+                        .absolute()
+                        .top_0()
+                        .left(relative(p.hp_threshold))
+                        .w(px(2.0))
+                        .h_full()
+                        .bg(rgb(0xffffff))
+                        .into_any_element()
+                })
+                .collect();
+
+            Some(
+                v_flex()
+                    .gap_0p5()
+                    .child(
+                        h_flex()
+                            .items_center()
+                            .justify_between()
+                            .child(
+                                div()
+                                    .text_sm()
+                                    .text_color(rgb(0xff4444))
+                                    .child(boss.def.name),
+                            )
+                            .child(
+                                div()
+                                    .text_xs()
+                                    .text_color(rgb(0xcccccc))
+                                    .child(format!("{}{}", phase, immunity)),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .relative()
+                            .w_full()
+                            .h(px(8.0))
+                            .rounded_sm()
+                            .bg(Hsla {
+                                h: 0.0,
+                                s: 0.0,
+                                l: 0.2,
+                                a: 0.8,
+                            })
+                            .child(
+                                div()
+                                    .h_full()
+                                    .w(relative(ratio))
+                                    .rounded_sm()
+                                    .bg(rgb(0xcc2222)),
+                            )
+                            .children(markers),
+                    )
+                    .into_any_element(),
+            )
+        })
+        .collect();
+
+    if bars.is_empty() {
+        return None;
+    }
+
+    Some(
+        v_flex()
+            .id("boss_bars")
+            .absolute()
+            .top_3()
+            .left(relative(0.25))
+            .w(relative(0.5))
+            .gap_2()
+            .children(bars),
+    )
+}
+
+/// Large name banner for the most recent boss spawn, fading with the event
+pub fn render_boss_banner(events: &[GameEvent]) -> Option<impl IntoElement + use<>> {
+    let (name, lifetime) = events.iter().rev().find_map(|event| match event.kind {
+        GameEventKind::BossSpawned { name } => Some((name, event.lifetime)),
+        _ => None,
+    })?;
+
+    Some(
+        div()
+            .id("boss_banner")
+            .absolute()
+            .top(relative(0.2))
+            .left_0()
+            .w_full()
+            .flex()
+            .justify_center()
+            .child(
+                div()
+                    .text_2xl()
+                    .text_color(Hsla {
+                        h: 0.0,
+                        s: 0.8,
+                        l: 0.55,
+                        a: lifetime.min(1.0),
+                    })
+                    .child(name),
+            ),
+    )
+}
+
+/// Toast for the latest unlocked achievement, bottom-center of the canvas
+pub fn render_achievement_toast(events: &[GameEvent]) -> Option<impl IntoElement + use<>> {
+    let (achievement, lifetime) = events.iter().rev().find_map(|event| match event.kind {
+        GameEventKind::AchievementUnlocked { achievement } => Some((achievement, event.lifetime)),
+        _ => None,
+    })?;
+    let alpha = lifetime.min(1.0);
+
+    Some(
+        div()
+            .id("achievement_toast")
+            .absolute()
+            .bottom_6()
+            .left_0()
+            .w_full()
+            .flex()
+            .justify_center()
+            .child(
+                v_flex()
+                    .items_center()
+                    .gap_1()
+                    .px_4()
+                    .py_2()
+                    .rounded_md()
+                    .bg(Hsla {
+                        h: 0.7,
+                        s: 0.3,
+                        l: 0.2,
+                        a: 0.9 * alpha,
+                    })
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(Hsla {
+                                h: 0.13,
+                                s: 0.9,
+                                l: 0.55,
+                                a: alpha,
+                            })
+                            .child("Succes debloque"),
+                    )
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(Hsla {
+                                h: 0.0,
+                                s: 0.0,
+                                l: 1.0,
+                                a: alpha,
+                            })
+                            .child(achievement.name()),
+                    )
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(Hsla {
+                                h: 0.8,
+                                s: 0.8,
+                                l: 0.7,
+                                a: alpha,
+                            })
+                            .child(achievement.reward_label()),
+                    ),
+            ),
+    )
+}
+
+const DAMAGE_METER_ROWS: usize = 6;
+
+/// Live damage meter overlay: top damage sources of the current run
+pub fn render_damage_meter(stats: &RunStats) -> impl IntoElement {
+    let total = stats.total_damage();
+    let rows: Vec<AnyElement> = stats
+        .ranked_sources()
+        .into_iter()
+        .filter(|s| s.stats.damage > 0.0)
+        .take(DAMAGE_METER_ROWS)
+        .map(|source| {
+            let share = if total > 0.0 {
+                source.stats.damage / total
+            } else {
+                0.0
+            };
+            let color = source.element.color();
+
+            v_flex()
+                .gap_0p5()
+                .child(
+                    h_flex()
+                        .items_center()
+                        .justify_between()
+                        .child(div().text_xs().text_color(color).child(source.name.clone()))
+                        .child(div().text_xs().text_color(rgb(0xcccccc)).child(format!(
+                            "{:.0} ({:.0}%)",
+                            source.stats.damage,
+                            share * 100.0
+                        ))),
+                )
+                .child(
+                    div()
+                        .w_full()
+                        .h(px(3.0))
+                        .rounded_sm()
+                        .bg(Hsla {
+                            h: 0.0,
+                            s: 0.0,
+                            l: 0.2,
+                            a: 0.8,
+                        })
+                        .child(div().h_full().w(relative(share)).rounded_sm().bg(color)),
+                )
+                .into_any_element()
+        })
+        .collect();
+
+    v_flex()
+        .id("damage_meter")
+        .absolute()
+        .top_3()
+        .right_3()
+        .w(px(180.0))
+        .p_2()
+        .gap_1()
+        .rounded_md()
+        .bg(Hsla {
+            h: 0.0,
+            s: 0.0,
+            l: 0.08,
+            a: 0.8,
+        })
+        .child(
+            h_flex()
+                .items_center()
+                .justify_between()
+                .child(div().text_xs().text_color(rgb(0xaaaaaa)).child("Degats"))
+                .child(
+                    div()
+                        .text_xs()
+                        .text_color(rgb(0x888888))
+                        .child(format!("{:.0}", total)),
+                ),
+        )
+        .children(rows)
+}
+
+fn summary_row(cells: [String; 6], color: impl Into<Hsla>) -> impl IntoElement {
+    let color = color.into();
+    let [name, rest @ ..] = cells;
+
+    h_flex()
+        .gap_2()
+        .child(div().w(px(140.0)).text_xs().text_color(color).child(name))
+        .children(rest.into_iter().map(|cell| {
+            div()
+                .w(px(60.0))
+                .text_xs()
+                .text_color(rgb(0xcccccc))
+                .child(cell)
+        }))
+}
+
+fn stats_cells(name: String, stats: &DamageStats) -> [String; 6] {
+    [
+        name,
+        format!("{:.0}", stats.damage),
+        format!("{}", stats.kills),
+        format!("{:.0}", stats.overkill),
+        format!("{}", stats.reactions),
+        format!("{}", stats.gold),
+    ]
+}
+
+fn summary_header(title: &'static str) -> impl IntoElement {
+    summary_row(
+        [
+            title.to_string(),
+            "Degats".to_string(),
+            "Kills".to_string(),
+            "Surplus".to_string(),
+            "Reac.".to_string(),
+            "Or".to_string(),
+        ],
+        rgb(0xaaaaaa),
+    )
+}
+
+/// End-of-run breakdown per tower, per element and per action type
+pub fn render_run_summary(stats: &RunStats) -> impl IntoElement {
+    let source_rows: Vec<AnyElement> = stats
+        .ranked_sources()
+        .into_iter()
+        .map(|source| {
+            summary_row(
+                stats_cells(source.name.clone(), &source.stats),
+                source.element.color(),
+            )
+            .into_any_element()
+        })
+        .collect();
+
+    let element_rows: Vec<AnyElement> = stats
+        .elements
+        .iter()
+        .map(|(element, element_stats)| {
+            summary_row(
+                stats_cells(element.name().to_string(), element_stats),
+                element.color(),
+            )
+            .into_any_element()
+        })
+        .collect();
+
+    let action_rows: Vec<AnyElement> = stats
+        .actions
+        .iter()
+        .map(|(action, action_stats)| {
+            summary_row(
+                stats_cells(action.name().to_string(), action_stats),
+                rgb(0xffffff),
+            )
+            .into_any_element()
+        })
+        .collect();
+
+    let reactions = stats
+        .reactions
+        .iter()
+        .map(|(reaction, count)| format!("{} x{}", reaction.name(), count))
+        .collect::<Vec<_>>()
+        .join("  ");
+
+    div()
+        .id("run_summary")
+        .max_h(px(360.0))
+        .overflow_y_scroll()
+        .p_3()
+        .rounded_md()
+        .bg(Hsla {
+            h: 0.0,
+            s: 0.0,
+            l: 0.1,
+            a: 0.9,
+        })
+        .child(
+            v_flex()
+                .gap_1()
+                .child(summary_header("Source"))
+                .children(source_rows)
+                .child(div().h(px(6.0)))
+                .child(summary_header("Element"))
+                .children(element_rows)
+                .child(div().h(px(6.0)))
+                .child(summary_header("Action"))
+                .children(action_rows)
+                .when(!reactions.is_empty(), |this| {
+                    this.child(div().h(px(6.0))).child(
+                        div()
+                            .text_xs()
+                            .text_color(rgb(0xaaaaaa))
+                            .child(format!("Reactions: {}", reactions)),
+                    )
+                }),
+        )
+}